@@ -0,0 +1,318 @@
+//! Removal of personal information from replay files.
+
+use crate::data::chunks::Chunk;
+use crate::data::ticks::Tick;
+use crate::data::{Location, Replay as ReplayData, Span};
+use crate::ParseError;
+use nom_locate::LocatedSpan;
+use nom_tracable::TracableInfo;
+use std::collections::HashMap;
+
+/// The 64-bit Steam ID of the first individual account. Pseudonymous Steam IDs are generated as
+/// offsets from this value so that they retain the shape of a real Steam ID.
+const STEAM_ID_BASE: u64 = 76561197960265728;
+
+/// Text written in place of chat messages when `AnonymizeOptions::redact_messages` is enabled.
+const REDACTED_MESSAGE: &str = "[redacted]";
+
+/// Controls which categories of personal information `anonymize` rewrites. By default names,
+/// Steam IDs and profile IDs are replaced with pseudonyms and chat messages are kept with any
+/// mention of a player's name pseudonymized.
+
+#[derive(Debug, Clone)]
+pub struct AnonymizeOptions {
+    /// Replace the names of human players with pseudonyms of the form `Player N`, where `N` is the
+    /// player's position in the replay's player list. The same pseudonym is used for the sender of
+    /// the player's chat messages and for any mention of the name within chat text. AI players
+    /// keep their names.
+    pub names: bool,
+    /// Replace the Steam IDs of human players with pseudonymous IDs of the same length.
+    pub steam_ids: bool,
+    /// Replace the Relic profile IDs of human players with their position in the player list,
+    /// including the copies stored alongside each player item and chat message.
+    pub profile_ids: bool,
+    /// Replace the text of every chat message with a fixed placeholder instead of only
+    /// pseudonymizing player names within it.
+    pub redact_messages: bool,
+}
+
+impl Default for AnonymizeOptions {
+    fn default() -> Self {
+        Self {
+            names: true,
+            steam_ids: true,
+            profile_ids: true,
+            redact_messages: false,
+        }
+    }
+}
+
+/// Takes a byte slice containing a CoH3 replay and returns a copy of it with personal information
+/// replaced according to the given options. Pseudonyms are consistent across the whole file, and
+/// every length prefix affected by a rewritten string is recomputed, so the result can be parsed
+/// (and played back) just like the original. Any failure while parsing the input will return an
+/// error.
+///
+/// ```ignore
+/// fn main() {
+///     let data = include_bytes!("/path/to/replay.rec");
+///     let anonymized = vault::anonymize(data, &vault::AnonymizeOptions::default()).unwrap();
+///     let replay = vault::Replay::from_bytes(&anonymized);
+///     assert!(replay.is_ok())
+/// }
+/// ```
+pub fn anonymize<'a>(
+    input: &'a [u8],
+    options: &AnonymizeOptions,
+) -> Result<Vec<u8>, ParseError<'a>> {
+    let info = TracableInfo::new().parser_width(64).fold("term");
+    let span: Span = LocatedSpan::new_extra(input, info);
+    let (_, replay) = ReplayData::from_span(span)?;
+
+    let players = &replay.game_data().players;
+    let mut rewriter = Rewriter::default();
+    let mut pseudonyms = Pseudonyms::new(players.len() as u64 + 1);
+
+    for chunk in &replay.chunks {
+        rewriter.chunk_lengths(chunk);
+    }
+
+    for (idx, player) in players.iter().enumerate() {
+        if player.human == 0 {
+            continue;
+        }
+
+        let number = idx as u64 + 1;
+        pseudonyms.profile_ids.insert(player.profile_id, number);
+
+        if options.names {
+            let name = pseudonyms.player_name(&player.name, number);
+            rewriter.replace(player.name_location, encode_utf16_variable(&name));
+        }
+        if options.steam_ids {
+            let steam_id = (STEAM_ID_BASE + number).to_string();
+            rewriter.replace(player.steam_id_location, encode_utf16_variable(&steam_id));
+        }
+        if options.profile_ids {
+            rewriter.replace(player.profile_id_location, number.to_le_bytes().to_vec());
+
+            for location in player
+                .items
+                .iter()
+                .filter_map(|item| item.profile_id_location)
+            {
+                rewriter.replace(location, number.to_le_bytes().to_vec());
+            }
+        }
+    }
+
+    for tick in &replay.ticks {
+        let Tick::Message(tick) = tick else {
+            continue;
+        };
+
+        rewriter.length_prefix(tick.location);
+        if let Some(location) = tick.body_location {
+            rewriter.length_prefix(location);
+        }
+
+        if let (true, Some(location)) = (options.profile_ids, tick.sender_id_location) {
+            let sender_id = read_u64(input, location);
+            let number = pseudonyms.profile_ids.get(&sender_id).copied().unwrap_or(0);
+            rewriter.replace(location, number.to_le_bytes().to_vec());
+        }
+
+        for message in &tick.messages {
+            if options.names {
+                let name = pseudonyms.sender_name(&message.name);
+                rewriter.replace(message.name_location, encode_utf16_variable(&name));
+            }
+
+            let text = if options.redact_messages {
+                REDACTED_MESSAGE.to_string()
+            } else if options.names {
+                pseudonyms.scrub(&message.message)
+            } else {
+                continue;
+            };
+            rewriter.replace(message.message_location, encode_utf16_variable(&text));
+        }
+    }
+
+    Ok(rewriter.apply(input))
+}
+
+/// Consistent mapping between personal information and the pseudonyms replacing it.
+
+#[derive(Debug)]
+struct Pseudonyms {
+    names: HashMap<String, String>,
+    profile_ids: HashMap<u64, u64>,
+    next_number: u64,
+}
+
+impl Pseudonyms {
+    fn new(next_number: u64) -> Self {
+        Self {
+            names: HashMap::new(),
+            profile_ids: HashMap::new(),
+            next_number,
+        }
+    }
+
+    fn player_name(&mut self, name: &str, number: u64) -> String {
+        self.names
+            .entry(name.to_string())
+            .or_insert_with(|| format!("Player {}", number))
+            .clone()
+    }
+
+    /// Chat messages can be sent by participants that aren't in the player list, such as
+    /// observers. These are numbered after all of the players.
+    fn sender_name(&mut self, name: &str) -> String {
+        if let Some(pseudonym) = self.names.get(name) {
+            return pseudonym.clone();
+        }
+
+        let number = self.next_number;
+        self.next_number += 1;
+        self.player_name(name, number)
+    }
+
+    fn scrub(&self, text: &str) -> String {
+        let mut names: Vec<_> = self
+            .names
+            .iter()
+            .filter(|(name, _)| !name.is_empty())
+            .collect();
+        names.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+
+        let mut scrubbed = String::with_capacity(text.len());
+        let mut rest = text;
+
+        'outer: while !rest.is_empty() {
+            for (name, pseudonym) in &names {
+                if rest.len() >= name.len()
+                    && rest.is_char_boundary(name.len())
+                    && rest[..name.len()].eq_ignore_ascii_case(name)
+                {
+                    scrubbed.push_str(pseudonym);
+                    rest = &rest[name.len()..];
+                    continue 'outer;
+                }
+            }
+
+            let next = rest.chars().next().unwrap();
+            scrubbed.push(next);
+            rest = &rest[next.len_utf8()..];
+        }
+
+        scrubbed
+    }
+}
+
+/// Collects byte replacements along with the length prefixes that have to be kept in sync with
+/// them, and applies both to the original replay bytes in a single pass.
+
+#[derive(Debug, Default)]
+struct Rewriter {
+    edits: Vec<(Location, Vec<u8>)>,
+    length_prefixes: Vec<Location>,
+}
+
+impl Rewriter {
+    fn replace(&mut self, location: Location, bytes: Vec<u8>) {
+        self.edits.push((location, bytes));
+    }
+
+    /// Registers a little-endian u32 length prefix located at the start of `location` that
+    /// measures the remainder of `location`.
+    fn length_prefix(&mut self, location: Location) {
+        self.length_prefixes.push(location);
+    }
+
+    fn chunk_lengths(&mut self, chunk: &Chunk) {
+        match chunk {
+            Chunk::Fold(fold) => {
+                self.chunk_length(fold.header.length_location, fold.header.data_location());
+                for chunk in &fold.chunks {
+                    self.chunk_lengths(chunk);
+                }
+            }
+            Chunk::DataData(data) => {
+                self.chunk_length(data.header.length_location, data.header.data_location())
+            }
+            _ => {}
+        }
+    }
+
+    fn chunk_length(&mut self, length_location: Location, data_location: Location) {
+        self.length_prefix(Location {
+            offset: length_location.offset,
+            length: data_location.end() - length_location.offset,
+        });
+    }
+
+    fn apply(mut self, input: &[u8]) -> Vec<u8> {
+        for prefix in &self.length_prefixes {
+            let field = Location {
+                offset: prefix.offset,
+                length: 4,
+            };
+            let length = self
+                .edits
+                .iter()
+                .filter(|(location, _)| {
+                    location.offset >= field.end() && location.end() <= prefix.end()
+                })
+                .fold(read_u32(input, field) as usize, |acc, (location, bytes)| {
+                    acc + bytes.len() - location.length
+                });
+
+            self.edits
+                .push((field, (length as u32).to_le_bytes().to_vec()));
+        }
+
+        self.edits.sort_by_key(|(location, _)| location.offset);
+
+        let mut output = Vec::with_capacity(input.len());
+        let mut cursor = 0;
+
+        for (location, bytes) in &self.edits {
+            output.extend_from_slice(&input[cursor..location.offset]);
+            output.extend_from_slice(bytes);
+            cursor = location.end();
+        }
+        output.extend_from_slice(&input[cursor..]);
+
+        output
+    }
+}
+
+fn encode_utf16_variable(value: &str) -> Vec<u8> {
+    let units: Vec<u16> = value.encode_utf16().collect();
+    let mut bytes = Vec::with_capacity(4 + units.len() * 2);
+
+    bytes.extend_from_slice(&(units.len() as u32).to_le_bytes());
+    for unit in units {
+        bytes.extend_from_slice(&unit.to_le_bytes());
+    }
+
+    bytes
+}
+
+fn read_u32(input: &[u8], location: Location) -> u32 {
+    u32::from_le_bytes(
+        input[location.offset..location.offset + 4]
+            .try_into()
+            .unwrap(),
+    )
+}
+
+fn read_u64(input: &[u8], location: Location) -> u64 {
+    u64::from_le_bytes(
+        input[location.offset..location.offset + 8]
+            .try_into()
+            .unwrap(),
+    )
+}
@@ -0,0 +1,266 @@
+//! Parallel parsing of directories of replay files.
+//!
+//! `parse_dir` walks a directory, parses every replay it finds on a bounded pool of worker
+//! threads, and streams the results back as they complete:
+//!
+//! ```ignore
+//! fn main() {
+//!     let mut batch = vault::batch::parse_dir("/path/to/replays", &Default::default()).unwrap();
+//!
+//!     for (path, result) in &mut batch {
+//!         if let Err(err) = result {
+//!             println!("{}", err);
+//!         }
+//!     }
+//!
+//!     println!("{} replays parsed", batch.report().successes());
+//! }
+//! ```
+
+use crate::errors::{Error, ErrorKind};
+use crate::Replay;
+use std::any::Any;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{sync_channel, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// Controls which files `parse_dir` picks up and how many of them are parsed at once.
+
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    /// Maximum number of replays parsed concurrently. Defaults to the available parallelism of the
+    /// machine. A value of zero is treated as one.
+    pub workers: usize,
+    /// Whether to descend into subdirectories.
+    pub recursive: bool,
+    /// Only files with this extension are parsed, or every file if `None`. Defaults to `rec`.
+    pub extension: Option<String>,
+    /// Memory map each file instead of reading it into memory. See `Replay::from_path_mmap`.
+    #[cfg(feature = "mmap")]
+    pub mmap: bool,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            workers: thread::available_parallelism().map_or(1, |workers| workers.get()),
+            recursive: true,
+            extension: Some("rec".to_string()),
+            #[cfg(feature = "mmap")]
+            mmap: false,
+        }
+    }
+}
+
+/// Finds all replay files under the given directory and starts parsing them in parallel according
+/// to the given options. Parse results are streamed back in completion order by iterating over the
+/// returned `Batch`. Any failure while reading the directory tree will return an error with the
+/// failing path attached; failures parsing individual replays are instead reported alongside the
+/// replay's path.
+pub fn parse_dir<P: AsRef<Path>>(path: P, options: &BatchOptions) -> Result<Batch, Error> {
+    let mut paths = Vec::new();
+    collect_paths(path.as_ref(), options, &mut paths)?;
+    paths.sort();
+
+    let workers = options.workers.max(1).min(paths.len().max(1));
+    let queue = Arc::new(Mutex::new(paths.into_iter()));
+    let (sender, receiver) = sync_channel(workers);
+
+    let handles = (0..workers)
+        .map(|_| {
+            let queue = Arc::clone(&queue);
+            let sender = sender.clone();
+            let options = options.clone();
+
+            thread::spawn(move || loop {
+                let next = queue.lock().unwrap().next();
+                let Some(path) = next else {
+                    break;
+                };
+
+                let result = panic::catch_unwind(AssertUnwindSafe(|| parse_path(&path, &options)))
+                    .unwrap_or_else(|payload| {
+                        Err(Error::new(
+                            Some(&path),
+                            ErrorKind::Panic(panic_message(payload)),
+                        ))
+                    });
+                if sender.send((path, result)).is_err() {
+                    break;
+                }
+            })
+        })
+        .collect();
+
+    Ok(Batch {
+        receiver,
+        handles,
+        report: BatchReport::default(),
+    })
+}
+
+/// A batch of replays being parsed in the background. Iterating over a `Batch` yields each
+/// replay's path along with its parse result as soon as it's available. Every result yielded is
+/// also tallied into a `BatchReport`, which can be retrieved with `Batch::report`.
+
+#[derive(Debug)]
+pub struct Batch {
+    receiver: Receiver<(PathBuf, Result<Replay, Error>)>,
+    handles: Vec<JoinHandle<()>>,
+    report: BatchReport,
+}
+
+impl Batch {
+    /// Waits for all remaining replays to finish parsing and returns the aggregate report for the
+    /// whole batch, including results that were already yielded by iteration.
+    pub fn report(mut self) -> BatchReport {
+        for _ in &mut self {}
+        for handle in self.handles.drain(..) {
+            // workers catch panics while parsing, so there's nothing left to report here
+            let _ = handle.join();
+        }
+
+        self.report
+    }
+}
+
+impl Iterator for Batch {
+    type Item = (PathBuf, Result<Replay, Error>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, result) = self.receiver.recv().ok()?;
+        self.report.record(&path, &result);
+        Some((path, result))
+    }
+}
+
+/// Aggregate outcome of parsing a batch of replays.
+
+#[derive(Debug, Clone, Default)]
+pub struct BatchReport {
+    successes: usize,
+    failures: HashMap<FailureGroup, Vec<PathBuf>>,
+}
+
+impl BatchReport {
+    /// Number of replays that parsed successfully.
+    pub fn successes(&self) -> usize {
+        self.successes
+    }
+    /// Number of replays that failed to parse.
+    pub fn failure_count(&self) -> usize {
+        self.failures.values().map(Vec::len).sum()
+    }
+    /// Paths of the replays that failed to parse, grouped by the kind of failure and the game
+    /// version the replay was recorded on.
+    pub fn failures(&self) -> &HashMap<FailureGroup, Vec<PathBuf>> {
+        &self.failures
+    }
+
+    fn record(&mut self, path: &Path, result: &Result<Replay, Error>) {
+        match result {
+            Ok(_) => self.successes += 1,
+            Err(err) => self
+                .failures
+                .entry(FailureGroup {
+                    kind: FailureKind::from(err.kind()),
+                    version: err.version(),
+                })
+                .or_default()
+                .push(path.to_path_buf()),
+        }
+    }
+}
+
+/// Key used to group failures in a `BatchReport`.
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FailureGroup {
+    /// The kind of failure that occurred.
+    pub kind: FailureKind,
+    /// The game version of the failed replays, or `None` if the replay's header couldn't be read.
+    pub version: Option<u16>,
+}
+
+/// Comparable summary of an `ErrorKind`, discarding details specific to an individual failure
+/// such as the offset at which parsing stopped.
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FailureKind {
+    Io(io::ErrorKind),
+    Parse(nom::error::ErrorKind),
+    Incomplete,
+    Panic,
+}
+
+impl From<&ErrorKind> for FailureKind {
+    fn from(kind: &ErrorKind) -> FailureKind {
+        match kind {
+            ErrorKind::Io(err) => FailureKind::Io(err.kind()),
+            ErrorKind::Parse { code, .. } => FailureKind::Parse(*code),
+            ErrorKind::Incomplete => FailureKind::Incomplete,
+            ErrorKind::Panic(_) => FailureKind::Panic,
+        }
+    }
+}
+
+fn collect_paths(
+    dir: &Path,
+    options: &BatchOptions,
+    paths: &mut Vec<PathBuf>,
+) -> Result<(), Error> {
+    let entries = fs::read_dir(dir).map_err(|err| Error::new(Some(dir), ErrorKind::Io(err)))?;
+
+    for entry in entries {
+        let path = entry
+            .map_err(|err| Error::new(Some(dir), ErrorKind::Io(err)))?
+            .path();
+
+        if path.is_dir() {
+            if options.recursive {
+                collect_paths(&path, options, paths)?;
+            }
+        } else if matches_extension(&path, options) {
+            paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn matches_extension(path: &Path, options: &BatchOptions) -> bool {
+    match &options.extension {
+        Some(extension) => matches!(
+            path.extension(),
+            Some(ext) if ext.eq_ignore_ascii_case(extension.as_str())
+        ),
+        None => true,
+    }
+}
+
+fn parse_path(path: &Path, options: &BatchOptions) -> Result<Replay, Error> {
+    #[cfg(feature = "mmap")]
+    if options.mmap {
+        return Replay::from_path_mmap(path);
+    }
+
+    #[cfg(not(feature = "mmap"))]
+    let _ = options;
+
+    Replay::from_path(path)
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => String::new(),
+        },
+    }
+}
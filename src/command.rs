@@ -1,7 +1,10 @@
 //! Wrapper for Company of Heroes 3 player commands.
 
 use crate::{
-    command_data::{Pbgid, Sourced, SourcedIndex, SourcedPbgid, Unknown},
+    command_data::{
+        Ability, Cheat, CheatKind, Malformed, Pbgid, Placement, Selection, Sourced, SourcedIndex,
+        SourcedPbgid, SourcedTarget, Squad, SquadPbgid, SquadState, SquadTarget, Unknown,
+    },
     command_type::CommandType,
     data::ticks,
 };
@@ -19,92 +22,334 @@ use serde::{Deserialize, Serialize};
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::Command"))]
 pub enum Command {
+    AbandonTeamWeapon(Squad),
     BuildGlobalUpgrade(SourcedPbgid),
     BuildSquad(SourcedPbgid),
+    BuildStructure(SquadTarget),
     CancelConstruction(Sourced),
     CancelProduction(SourcedIndex),
-    ConstructEntity(Pbgid),
+    CancelQueuedCommand(Squad),
+    CancelRestoreWreck(Squad),
+    Capture(SquadTarget),
+    CaptureTeamWeapon(SquadTarget),
+    Cheat(Cheat),
+    ConstructEntity(Placement),
+    DefuseCharge(Squad),
+    DefuseMine(Squad),
+    Destroy(Squad),
+    DetonateCharges(Squad),
+    Evacuate(Squad),
+    FormationGroupAddSquad(Squad),
+    FormationGroupAttack(Squad),
+    FormationGroupAttackMove(Squad),
+    FormationGroupCreateBegin(Squad),
+    FormationGroupCreateEnd(Squad),
+    FormationGroupMove(Squad),
+    FormationGroupStop(Squad),
+    HoldPosition(SquadState),
+    HoldPositionOff(SquadState),
+    HoldPositionOn(SquadState),
+    JoinFormationGroup(Squad),
+    Load(SquadTarget),
+    PlaceCharge(Squad),
+    RallyPoint(SourcedTarget),
+    Recrew(SquadTarget),
+    Reinforce(SquadPbgid),
+    Repair(Squad),
+    RestoreWreck(Squad),
     SelectBattlegroup(Pbgid),
     SelectBattlegroupAbility(Pbgid),
+    SetCombatStance(SquadState),
+    SetMoveType(SquadState),
+    SetWeaponPreference(SquadState),
+    SetupTeamWeapon(Squad),
+    SquadUpgrade(SquadPbgid),
+    Stop(Squad),
+    StopAbility(SquadPbgid),
+    Surprise(SquadState),
+    Teardown(Squad),
+    Unload(Squad),
+    UnloadSquads(Squad),
     UseAbility(SourcedPbgid),
     UseBattlegroupAbility(Pbgid),
+    UseSquadAbility(Ability),
     Unknown(Unknown),
+    Malformed(Malformed),
 }
 
 impl Command {
     pub(crate) fn from_data_command_at_tick(command: ticks::Command, tick: u32) -> Self {
-        match command.data {
-            ticks::CommandData::Pbgid(pbgid) => match command.action_type {
-                CommandType::PCMD_Ability => {
-                    Self::UseBattlegroupAbility(Pbgid::new(tick, command.index, pbgid))
+        let ticks::Command {
+            action_type,
+            index,
+            data,
+            selection,
+            bytes,
+            ..
+        } = command;
+        let selection = Selection::new(&selection);
+
+        match data {
+            ticks::CommandData::Ability(pbgid, target) => match (action_type, pbgid) {
+                (CommandType::PCMD_Ability, Some(pbgid)) => {
+                    Self::UseBattlegroupAbility(Pbgid::new(tick, index, pbgid, target))
+                }
+                (CommandType::PCMD_Ability, None) => {
+                    Self::missing_pbgid(action_type, index, bytes, tick)
+                }
+                (CommandType::SCMD_Ability, _) => {
+                    Self::UseSquadAbility(Ability::new(tick, index, pbgid, target, selection))
                 }
+                _ => Self::unexpected_data(action_type, index, bytes, tick, "ability"),
+            },
+            ticks::CommandData::Cheat(argument) => {
+                let kind = match action_type {
+                    CommandType::PCMD_CheatBuildTime => CheatKind::BuildTime,
+                    CommandType::PCMD_CheatIgnoreCosts => CheatKind::IgnoreCosts,
+                    CommandType::PCMD_CheatResources => CheatKind::Resources,
+                    CommandType::PCMD_CheatRevealAll => CheatKind::RevealAll,
+                    _ => return Self::unexpected_data(action_type, index, bytes, tick, "cheat"),
+                };
+
+                Self::Cheat(Cheat::new(tick, index, kind, argument))
+            }
+            ticks::CommandData::Pbgid(pbgid) => match action_type {
                 CommandType::PCMD_InstantUpgrade => {
-                    Self::SelectBattlegroup(Pbgid::new(tick, command.index, pbgid))
+                    Self::SelectBattlegroup(Pbgid::new(tick, index, pbgid, None))
                 }
-                CommandType::PCMD_PlaceAndConstructEntities => {
-                    Self::ConstructEntity(Pbgid::new(tick, command.index, pbgid))
+                CommandType::PCMD_StopAbility => {
+                    Self::StopAbility(SquadPbgid::new(tick, index, pbgid, selection))
                 }
                 CommandType::PCMD_TentativeUpgrade => {
-                    Self::SelectBattlegroupAbility(Pbgid::new(tick, command.index, pbgid))
+                    Self::SelectBattlegroupAbility(Pbgid::new(tick, index, pbgid, None))
                 }
-                _ => panic!(
-                    "a pbgid command isn't being handled here! command type {:?}",
-                    command.action_type
-                ),
+                _ => Self::unexpected_data(action_type, index, bytes, tick, "pbgid"),
             },
-            ticks::CommandData::SourcedPbgid(pbgid, source_identifier) => match command.action_type
+            ticks::CommandData::Placement(pbgid, start, end, facing, builders) => match action_type
             {
-                CommandType::CMD_Ability => Self::UseAbility(SourcedPbgid::new(
+                CommandType::PCMD_PlaceAndConstructEntities => Self::ConstructEntity(
+                    Placement::new(tick, index, pbgid, start, end, facing, builders),
+                ),
+                _ => Self::unexpected_data(action_type, index, bytes, tick, "placement"),
+            },
+            ticks::CommandData::SourcedAbility(source_identifier, pbgid, target) => {
+                match (action_type, pbgid) {
+                    (CommandType::CMD_Ability, Some(pbgid)) => Self::UseAbility(SourcedPbgid::new(
+                        tick,
+                        index,
+                        pbgid,
+                        source_identifier,
+                        target,
+                        selection,
+                    )),
+                    (CommandType::CMD_Ability, None) => {
+                        Self::missing_pbgid(action_type, index, bytes, tick)
+                    }
+                    _ => Self::unexpected_data(action_type, index, bytes, tick, "sourced ability"),
+                }
+            }
+            ticks::CommandData::SourcedPbgid(pbgid, source_identifier) => match action_type {
+                CommandType::CMD_BuildSquad => Self::BuildSquad(SourcedPbgid::new(
                     tick,
-                    command.index,
+                    index,
                     pbgid,
                     source_identifier,
+                    None,
+                    selection,
                 )),
-                CommandType::CMD_BuildSquad => Self::BuildSquad(SourcedPbgid::new(
+                CommandType::CMD_Upgrade => Self::BuildGlobalUpgrade(SourcedPbgid::new(
                     tick,
-                    command.index,
+                    index,
                     pbgid,
                     source_identifier,
+                    None,
+                    selection,
                 )),
-                CommandType::CMD_Upgrade => Self::BuildGlobalUpgrade(SourcedPbgid::new(
+                _ => Self::unexpected_data(action_type, index, bytes, tick, "sourced pbgid"),
+            },
+            ticks::CommandData::Sourced(source_identifier) => match action_type {
+                CommandType::CMD_CancelConstruction => Self::CancelConstruction(Sourced::new(
                     tick,
-                    command.index,
-                    pbgid,
+                    index,
                     source_identifier,
+                    selection,
                 )),
-                _ => panic!(
-                    "a sourced pbgid command isn't being handled here! command type {:?}",
-                    command.action_type
-                ),
+                _ => Self::unexpected_data(action_type, index, bytes, tick, "sourced"),
             },
-            ticks::CommandData::Sourced(source_identifier) => match command.action_type {
-                CommandType::CMD_CancelConstruction => {
-                    Self::CancelConstruction(Sourced::new(tick, command.index, source_identifier))
+            ticks::CommandData::SourcedIndex(source_identifier, queue_index) => match action_type {
+                CommandType::CMD_CancelProduction => Self::CancelProduction(SourcedIndex::new(
+                    tick,
+                    index,
+                    source_identifier,
+                    queue_index,
+                    selection,
+                )),
+                _ => Self::unexpected_data(action_type, index, bytes, tick, "sourced index"),
+            },
+            ticks::CommandData::SourcedTarget(target) => match action_type {
+                CommandType::CMD_RallyPoint | CommandType::SCMD_RallyPoint => {
+                    Self::RallyPoint(SourcedTarget::new(tick, index, target, selection))
                 }
-                _ => panic!(
-                    "a sourced command isn't being handled here! command type {:?}",
-                    command.action_type
-                ),
+                _ => Self::unexpected_data(action_type, index, bytes, tick, "sourced target"),
             },
-            ticks::CommandData::SourcedIndex(source_identifier, queue_index) => {
-                match command.action_type {
-                    CommandType::CMD_CancelProduction => Self::CancelProduction(SourcedIndex::new(
-                        tick,
-                        command.index,
-                        source_identifier,
-                        queue_index,
-                    )),
-                    _ => panic!(
-                        "a sourced command isn't being handled here! command type {:?}",
-                        command.action_type
-                    ),
+            ticks::CommandData::Squad(target) => match action_type {
+                CommandType::SCMD_AbandonTeamWeapon => {
+                    Self::AbandonTeamWeapon(Squad::new(tick, index, target, selection))
+                }
+                CommandType::SCMD_InstantSetupTeamWeapon | CommandType::SCMD_SetupTeamWeapon => {
+                    Self::SetupTeamWeapon(Squad::new(tick, index, target, selection))
+                }
+                CommandType::SCMD_CancelQueuedCommand => {
+                    Self::CancelQueuedCommand(Squad::new(tick, index, target, selection))
+                }
+                CommandType::CMD_Stop | CommandType::SCMD_Stop => {
+                    Self::Stop(Squad::new(tick, index, target, selection))
+                }
+                CommandType::FCMD_FormationSquadGroupAttack => {
+                    Self::FormationGroupAttack(Squad::new(tick, index, target, selection))
+                }
+                CommandType::FCMD_FormationSquadGroupAttackMove => {
+                    Self::FormationGroupAttackMove(Squad::new(tick, index, target, selection))
+                }
+                CommandType::FCMD_FormationSquadGroupMove => {
+                    Self::FormationGroupMove(Squad::new(tick, index, target, selection))
+                }
+                CommandType::FCMD_FormationSquadGroupStop => {
+                    Self::FormationGroupStop(Squad::new(tick, index, target, selection))
+                }
+                CommandType::PCMD_FormationSquadGroupCreateBegin => {
+                    Self::FormationGroupCreateBegin(Squad::new(tick, index, target, selection))
+                }
+                CommandType::PCMD_FormationSquadGroupAddSquad => {
+                    Self::FormationGroupAddSquad(Squad::new(tick, index, target, selection))
+                }
+                CommandType::PCMD_FormationSquadGroupCreateEnd => {
+                    Self::FormationGroupCreateEnd(Squad::new(tick, index, target, selection))
+                }
+                CommandType::SCMD_JoinFormationSquadGroup => {
+                    Self::JoinFormationGroup(Squad::new(tick, index, target, selection))
+                }
+                CommandType::SCMD_DefuseCharge => {
+                    Self::DefuseCharge(Squad::new(tick, index, target, selection))
+                }
+                CommandType::CMD_DefuseMine | CommandType::SCMD_DefuseMine => {
+                    Self::DefuseMine(Squad::new(tick, index, target, selection))
+                }
+                CommandType::PCMD_DetonateCharges => {
+                    Self::DetonateCharges(Squad::new(tick, index, target, selection))
+                }
+                CommandType::CMD_CancelRestoreWreck => {
+                    Self::CancelRestoreWreck(Squad::new(tick, index, target, selection))
+                }
+                CommandType::CMD_Destroy | CommandType::SCMD_Destroy => {
+                    Self::Destroy(Squad::new(tick, index, target, selection))
+                }
+                CommandType::CMD_Repair => Self::Repair(Squad::new(tick, index, target, selection)),
+                CommandType::CMD_RestoreWreck => {
+                    Self::RestoreWreck(Squad::new(tick, index, target, selection))
+                }
+                CommandType::CMD_Teardown => {
+                    Self::Teardown(Squad::new(tick, index, target, selection))
+                }
+                CommandType::CMD_PlaceCharge | CommandType::SCMD_PlaceCharge => {
+                    Self::PlaceCharge(Squad::new(tick, index, target, selection))
+                }
+                CommandType::CMD_Evacuate | CommandType::SCMD_Evacuate => {
+                    Self::Evacuate(Squad::new(tick, index, target, selection))
+                }
+                CommandType::CMD_Unload | CommandType::SCMD_Unload => {
+                    Self::Unload(Squad::new(tick, index, target, selection))
+                }
+                CommandType::CMD_UnloadSquads | CommandType::SCMD_UnloadSquads => {
+                    Self::UnloadSquads(Squad::new(tick, index, target, selection))
+                }
+                _ => Self::unexpected_data(action_type, index, bytes, tick, "squad"),
+            },
+            ticks::CommandData::SquadPbgid(pbgid) => match action_type {
+                CommandType::SCMD_ReinforceUnit | CommandType::SCMD_InstantReinforceUnit => {
+                    Self::Reinforce(SquadPbgid::new(tick, index, pbgid, selection))
+                }
+                CommandType::CMD_StopAbility | CommandType::SCMD_StopAbility => {
+                    Self::StopAbility(SquadPbgid::new(tick, index, pbgid, selection))
+                }
+                CommandType::SCMD_InstantUpgrade | CommandType::SCMD_Upgrade => {
+                    Self::SquadUpgrade(SquadPbgid::new(tick, index, pbgid, selection))
+                }
+                _ => Self::unexpected_data(action_type, index, bytes, tick, "squad pbgid"),
+            },
+            ticks::CommandData::SquadState(argument) => {
+                let state = SquadState::new(tick, index, argument, selection);
+
+                match action_type {
+                    CommandType::CMD_HoldPositionOff => Self::HoldPositionOff(state),
+                    CommandType::CMD_HoldPositionOn => Self::HoldPositionOn(state),
+                    CommandType::SCMD_CombatStance => Self::SetCombatStance(state),
+                    CommandType::SCMD_HoldPosition => Self::HoldPosition(state),
+                    CommandType::SCMD_SetMoveType => Self::SetMoveType(state),
+                    CommandType::SCMD_Surprise => Self::Surprise(state),
+                    CommandType::SCMD_WeaponPreference => Self::SetWeaponPreference(state),
+                    _ => Self::unexpected_data(action_type, index, bytes, tick, "squad state"),
                 }
             }
+            ticks::CommandData::SquadTarget(target) => match action_type {
+                CommandType::CMD_BuildStructure | CommandType::SCMD_BuildStructure => {
+                    Self::BuildStructure(SquadTarget::new(tick, index, target, selection))
+                }
+                CommandType::CMD_Capture | CommandType::SCMD_Capture => {
+                    Self::Capture(SquadTarget::new(tick, index, target, selection))
+                }
+                CommandType::SCMD_CaptureTeamWeapon => {
+                    Self::CaptureTeamWeapon(SquadTarget::new(tick, index, target, selection))
+                }
+                CommandType::CMD_InstantLoad
+                | CommandType::CMD_Load
+                | CommandType::SCMD_InstantLoad
+                | CommandType::SCMD_Load => {
+                    Self::Load(SquadTarget::new(tick, index, target, selection))
+                }
+                CommandType::SCMD_Recrew => {
+                    Self::Recrew(SquadTarget::new(tick, index, target, selection))
+                }
+                _ => Self::unexpected_data(action_type, index, bytes, tick, "squad target"),
+            },
             ticks::CommandData::Unknown => {
-                Self::Unknown(Unknown::new(tick, command.index, command.action_type))
+                Self::Unknown(Unknown::new(tick, index, action_type, selection))
+            }
+            ticks::CommandData::Malformed(reason) => {
+                Self::Malformed(Malformed::new(tick, index, action_type, bytes, reason))
             }
         }
     }
+
+    fn unexpected_data(
+        action_type: CommandType,
+        index: u32,
+        bytes: Vec<u8>,
+        tick: u32,
+        data_format: &str,
+    ) -> Self {
+        Self::Malformed(Malformed::new(
+            tick,
+            index,
+            action_type,
+            bytes,
+            format!(
+                "{} data isn't handled for command type {:?}",
+                data_format, action_type
+            ),
+        ))
+    }
+
+    /// Only squad abilities can be used without recording the ability's pbgid.
+    fn missing_pbgid(action_type: CommandType, index: u32, bytes: Vec<u8>, tick: u32) -> Self {
+        Self::Malformed(Malformed::new(
+            tick,
+            index,
+            action_type,
+            bytes,
+            format!("ability pbgid missing for command type {:?}", action_type),
+        ))
+    }
 }
 
 // this is safe as Command does not contain any Ruby types
@@ -124,12 +369,16 @@ pub struct RawCommand {
 
 #[cfg(feature = "raw")]
 impl RawCommand {
-    pub(crate) fn from_data_command_at_tick(command: ticks::Command, tick: u32) -> Self {
+    pub(crate) fn from_data_command_at_tick(
+        command: ticks::Command,
+        player_id: u8,
+        tick: u32,
+    ) -> Self {
         Self {
             tick,
             index: command.index,
             action_type: command.action_type,
-            player_id: command.player_id,
+            player_id,
             bytes: command.bytes,
         }
     }
@@ -0,0 +1,66 @@
+use crate::command_data::{Selection, Target};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A command format used when a squad ability is activated, that contains the ability's pbgid, the
+/// squads that cast it and what it was aimed at.
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Ability {
+    tick: u32,
+    index: u32,
+    pbgid: Option<u32>,
+    target: Option<Target>,
+    selection: Selection,
+}
+
+impl Ability {
+    pub(crate) fn new(
+        tick: u32,
+        index: u32,
+        pbgid: Option<u32>,
+        target: Option<Target>,
+        selection: Selection,
+    ) -> Self {
+        Self {
+            tick,
+            index,
+            pbgid,
+            target,
+            selection,
+        }
+    }
+
+    /// This value is the tick at which the command was found while parsing the replay, which
+    /// represents the time in the replay at which it was executed. Because CoH3's engine runs at 8
+    /// ticks per second, you can divide this value by 8 to get the number of seconds since the
+    /// replay began, which will tell you when this command was executed.
+    pub fn tick(&self) -> u32 {
+        self.tick
+    }
+    /// This value is the index of the command relative to the player who issued the command.
+    /// Indexes start at 1 and increment on every player-issued command, which means you should be
+    /// able to look at the maximum index value of the commands associated with a player to
+    /// determine how many commands that player issued in a given game.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+    /// Internal ID that uniquely identifies the ability used. This value can be matched to CoH3
+    /// attribute files in order to determine the ability in question. Squad abilities used by
+    /// right clicking a target, rather than picked from the squad's ability menu, don't record
+    /// the ability used, in which case this will be `None`.
+    pub fn pbgid(&self) -> Option<u32> {
+        self.pbgid
+    }
+    /// The position, entity or squad the ability was aimed at, or `None` if the ability doesn't
+    /// need a target, such as sprint.
+    pub fn target(&self) -> Option<Target> {
+        self.target
+    }
+    /// The squads that cast the ability.
+    pub fn selection(&self) -> &Selection {
+        &self.selection
+    }
+}
@@ -0,0 +1,52 @@
+use crate::command_data::CheatKind;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A command format used when a player activates a cheat, that contains the kind of cheat and the
+/// command's undecoded data. No sample replay contains cheats, so the layout of their parameters is
+/// unknown.
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Cheat {
+    tick: u32,
+    index: u32,
+    kind: CheatKind,
+    argument: Vec<u8>,
+}
+
+impl Cheat {
+    pub(crate) fn new(tick: u32, index: u32, kind: CheatKind, argument: Vec<u8>) -> Self {
+        Self {
+            tick,
+            index,
+            kind,
+            argument,
+        }
+    }
+
+    /// This value is the tick at which the command was found while parsing the replay, which
+    /// represents the time in the replay at which it was executed. Because CoH3's engine runs at 8
+    /// ticks per second, you can divide this value by 8 to get the number of seconds since the
+    /// replay began, which will tell you when this command was executed.
+    pub fn tick(&self) -> u32 {
+        self.tick
+    }
+    /// This value is the index of the command relative to the player who issued the command.
+    /// Indexes start at 1 and increment on every player-issued command, which means you should be
+    /// able to look at the maximum index value of the commands associated with a player to
+    /// determine how many commands that player issued in a given game.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+    /// The kind of cheat that was activated.
+    pub fn kind(&self) -> CheatKind {
+        self.kind
+    }
+    /// Every byte of the command following its index, undecoded. Any parameters of the cheat, such
+    /// as the amount of resources granted, are somewhere in here, but their layout is unknown.
+    pub fn argument(&self) -> &[u8] {
+        &self.argument
+    }
+}
@@ -0,0 +1,18 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The cheats a player can activate in a match, which are only available with the developer
+/// console enabled.
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CheatKind {
+    /// Grants the player additional resources.
+    Resources,
+    /// Removes the fog of war for the player.
+    RevealAll,
+    /// Changes how long the player's units and upgrades take to build.
+    BuildTime,
+    /// Lets the player build units and upgrades without paying for them.
+    IgnoreCosts,
+}
@@ -0,0 +1,67 @@
+use crate::command_type::CommandType;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A command that couldn't be parsed. Rather than failing the whole replay, the command's raw
+/// bytes are preserved alongside a description of what went wrong, and parsing continues with the
+/// next command.
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Malformed {
+    tick: u32,
+    index: u32,
+    action_type: CommandType,
+    bytes: Vec<u8>,
+    reason: String,
+}
+
+impl Malformed {
+    pub(crate) fn new(
+        tick: u32,
+        index: u32,
+        action_type: CommandType,
+        bytes: Vec<u8>,
+        reason: String,
+    ) -> Self {
+        Self {
+            tick,
+            index,
+            action_type,
+            bytes,
+            reason,
+        }
+    }
+
+    /// This value is the tick at which the command was found while parsing the replay, which
+    /// represents the time in the replay at which it was executed. Because CoH3's engine runs at 8
+    /// ticks per second, you can divide this value by 8 to get the number of seconds since the
+    /// replay began, which will tell you when this command was executed.
+    pub fn tick(&self) -> u32 {
+        self.tick
+    }
+    /// This value is the index of the command relative to the player who issued the command.
+    /// Indexes start at 1 and increment on every player-issued command, which means you should be
+    /// able to look at the maximum index value of the commands associated with a player to
+    /// determine how many commands that player issued in a given game. If the index couldn't be
+    /// read, this will be 0.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+    /// This value identifies the type of the command (build, move, stop, etc.), if it could be
+    /// read. Commands with similar functionality can be grouped by this value.
+    pub fn action_type(&self) -> CommandType {
+        self.action_type
+    }
+    /// The raw bytes of the command that failed to parse, starting from the byte following the
+    /// command type. If the command's length couldn't be read, this is every remaining byte in
+    /// the command's bundle.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+    /// A human-readable description of why the command couldn't be parsed.
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
@@ -1,13 +1,39 @@
 //! Representations of replay command data formats.
 
+mod ability;
+mod cheat;
+mod cheat_kind;
+mod malformed;
 mod pbgid;
+mod placement;
+mod position;
+mod selection;
 mod sourced;
 mod sourced_index;
 mod sourced_pbgid;
+mod sourced_target;
+mod squad;
+mod squad_pbgid;
+mod squad_state;
+mod squad_target;
+mod target;
 mod unknown;
 
+pub use crate::command_data::ability::Ability;
+pub use crate::command_data::cheat::Cheat;
+pub use crate::command_data::cheat_kind::CheatKind;
+pub use crate::command_data::malformed::Malformed;
 pub use crate::command_data::pbgid::Pbgid;
+pub use crate::command_data::placement::Placement;
+pub use crate::command_data::position::Position;
+pub use crate::command_data::selection::Selection;
 pub use crate::command_data::sourced::Sourced;
 pub use crate::command_data::sourced_index::SourcedIndex;
 pub use crate::command_data::sourced_pbgid::SourcedPbgid;
+pub use crate::command_data::sourced_target::SourcedTarget;
+pub use crate::command_data::squad::Squad;
+pub use crate::command_data::squad_pbgid::SquadPbgid;
+pub use crate::command_data::squad_state::SquadState;
+pub use crate::command_data::squad_target::SquadTarget;
+pub use crate::command_data::target::Target;
 pub use crate::command_data::unknown::Unknown;
@@ -1,7 +1,10 @@
+use crate::command_data::Target;
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-/// A simple command format that contains just an entity pbgid.
+/// A simple command format that contains just an entity pbgid, along with the target of
+/// battlegroup abilities.
 
 #[derive(Debug, Copy, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -9,11 +12,17 @@ pub struct Pbgid {
     tick: u32,
     index: u32,
     pbgid: u32,
+    target: Option<Target>,
 }
 
 impl Pbgid {
-    pub(crate) fn new(tick: u32, index: u32, pbgid: u32) -> Self {
-        Self { tick, index, pbgid }
+    pub(crate) fn new(tick: u32, index: u32, pbgid: u32, target: Option<Target>) -> Self {
+        Self {
+            tick,
+            index,
+            pbgid,
+            target,
+        }
     }
 
     /// This value is the tick at which the command was found while parsing the replay, which
@@ -36,4 +45,9 @@ impl Pbgid {
     pub fn pbgid(&self) -> u32 {
         self.pbgid
     }
+    /// The position, entity or squad a battlegroup ability was aimed at. This is `None` for
+    /// abilities that don't need a target and for battlegroup selections.
+    pub fn target(&self) -> Option<Target> {
+        self.target
+    }
 }
@@ -0,0 +1,92 @@
+use crate::command_data::Position;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A command format used when placing a structure, such as a building, bunker or line of wire,
+/// that contains the structure's pbgid, where and how it was placed, and the squads ordered to
+/// build it.
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Placement {
+    tick: u32,
+    index: u32,
+    pbgid: u32,
+    start: Position,
+    end: Position,
+    facing: Position,
+    builders: Vec<u32>,
+}
+
+impl Placement {
+    pub(crate) fn new(
+        tick: u32,
+        index: u32,
+        pbgid: u32,
+        start: Position,
+        end: Position,
+        facing: Position,
+        builders: Vec<u32>,
+    ) -> Self {
+        Self {
+            tick,
+            index,
+            pbgid,
+            start,
+            end,
+            facing,
+            builders,
+        }
+    }
+
+    /// This value is the tick at which the command was found while parsing the replay, which
+    /// represents the time in the replay at which it was executed. Because CoH3's engine runs at 8
+    /// ticks per second, you can divide this value by 8 to get the number of seconds since the
+    /// replay began, which will tell you when this command was executed.
+    pub fn tick(&self) -> u32 {
+        self.tick
+    }
+    /// This value is the index of the command relative to the player who issued the command.
+    /// Indexes start at 1 and increment on every player-issued command, which means you should be
+    /// able to look at the maximum index value of the commands associated with a player to
+    /// determine how many commands that player issued in a given game.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+    /// Internal ID that uniquely identifies entity associated with the command. This value can be
+    /// matched to CoH3 attribute files in order to determine the entity in question. Note that,
+    /// while rare, it is possible that this value may change between patches for the same entity.
+    pub fn pbgid(&self) -> u32 {
+        self.pbgid
+    }
+    /// The position the structure was placed at. For structures dragged out in a line, such as
+    /// walls or wire, this is where the drag began.
+    pub fn start(&self) -> Position {
+        self.start
+    }
+    /// The position a dragged line of structures ends at. The same as `start` for structures
+    /// placed at a single point.
+    pub fn end(&self) -> Position {
+        self.end
+    }
+    /// A point next to `start` in the direction the structure is facing.
+    pub fn facing(&self) -> Position {
+        self.facing
+    }
+    /// The direction the structure is facing, in radians, measured in the horizontal plane from
+    /// the positive x axis towards the positive z axis.
+    pub fn rotation(&self) -> f32 {
+        (self.facing.z() - self.start.z()).atan2(self.facing.x() - self.start.x())
+    }
+    /// Whether the structure was dragged out in a line from `start` to `end`, as is done with
+    /// walls, sandbags and wire, rather than placed at a single point.
+    pub fn is_drag(&self) -> bool {
+        self.start != self.end
+    }
+    /// Identifiers of the squads ordered to build the structure. Note that these don't appear to
+    /// share the numbering used by squad IDs in command selections.
+    pub fn builders(&self) -> &[u32] {
+        &self.builders
+    }
+}
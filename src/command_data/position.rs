@@ -0,0 +1,41 @@
+use crate::data::{ParserResult, Span};
+use nom::{combinator::map, number::complete::le_f32, sequence::tuple};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A point in world coordinates. The `x` and `z` axes span the map horizontally, while `y` is the
+/// height of the terrain at that point.
+
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Position {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+impl Position {
+    pub(crate) fn new(x: f32, y: f32, z: f32) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Horizontal coordinate along the map's x axis.
+    pub fn x(&self) -> f32 {
+        self.x
+    }
+    /// Height of the position.
+    pub fn y(&self) -> f32 {
+        self.y
+    }
+    /// Horizontal coordinate along the map's z axis.
+    pub fn z(&self) -> f32 {
+        self.z
+    }
+
+    pub(crate) fn parse(input: Span) -> ParserResult<Position> {
+        map(tuple((le_f32, le_f32, le_f32)), |(x, y, z)| {
+            Position::new(x, y, z)
+        })(input)
+    }
+}
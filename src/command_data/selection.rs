@@ -0,0 +1,51 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The set of squads and entities a command was issued to. Entity and squad commands record the
+/// IDs of every unit selected by the player at the time the command was given, so a command issued
+/// to a group of five squads will contain all five squad IDs.
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Selection {
+    squads: Vec<u32>,
+    entities: Vec<u32>,
+}
+
+impl Selection {
+    const KIND_MASK: u32 = 0xf000_0000;
+    const ENTITY: u32 = 0x1000_0000;
+    const SQUAD: u32 = 0x2000_0000;
+
+    pub(crate) fn new(ids: &[u32]) -> Self {
+        let (squads, entities) = ids
+            .iter()
+            .filter(|&id| matches!(id & Self::KIND_MASK, Self::ENTITY | Self::SQUAD))
+            .partition::<Vec<u32>, _>(|&id| id & Self::KIND_MASK == Self::SQUAD);
+
+        Self {
+            squads: squads.iter().map(|id| id & !Self::KIND_MASK).collect(),
+            entities: entities.iter().map(|id| id & !Self::KIND_MASK).collect(),
+        }
+    }
+
+    /// Identifiers given by the game engine to the selected squads. If you know the identifier for
+    /// a given squad, you can use this value to link the command to that squad.
+    pub fn squads(&self) -> &[u32] {
+        &self.squads
+    }
+    /// Identifiers given by the game engine to the selected entities, such as buildings or
+    /// vehicles selected on their own. If you know the identifier for a given entity, you can use
+    /// this value to link the command to that entity.
+    pub fn entities(&self) -> &[u32] {
+        &self.entities
+    }
+    /// Total number of squads and entities selected.
+    pub fn len(&self) -> usize {
+        self.squads.len() + self.entities.len()
+    }
+    /// Whether nothing was selected, or the selection couldn't be decoded for this command.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
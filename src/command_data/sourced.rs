@@ -1,22 +1,26 @@
+use crate::command_data::Selection;
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 /// A simple command format that contains just a source identifier.
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Sourced {
     tick: u32,
     index: u32,
     source_identifier: u16,
+    selection: Selection,
 }
 
 impl Sourced {
-    pub(crate) fn new(tick: u32, index: u32, source_identifier: u16) -> Self {
+    pub(crate) fn new(tick: u32, index: u32, source_identifier: u16, selection: Selection) -> Self {
         Self {
             tick,
             index,
             source_identifier,
+            selection,
         }
     }
 
@@ -40,4 +44,8 @@ impl Sourced {
     pub fn source_identifier(&self) -> u16 {
         self.source_identifier
     }
+    /// The squads and entities the command was issued to.
+    pub fn selection(&self) -> &Selection {
+        &self.selection
+    }
 }
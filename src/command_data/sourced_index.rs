@@ -1,24 +1,34 @@
+use crate::command_data::Selection;
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 /// A command format with both a source identifier and a queue index.
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SourcedIndex {
     tick: u32,
     index: u32,
     source_identifier: u16,
     queue_index: u32,
+    selection: Selection,
 }
 
 impl SourcedIndex {
-    pub(crate) fn new(tick: u32, index: u32, source_identifier: u16, queue_index: u32) -> Self {
+    pub(crate) fn new(
+        tick: u32,
+        index: u32,
+        source_identifier: u16,
+        queue_index: u32,
+        selection: Selection,
+    ) -> Self {
         Self {
             tick,
             index,
             source_identifier,
             queue_index,
+            selection,
         }
     }
 
@@ -50,4 +60,8 @@ impl SourcedIndex {
     pub fn queue_index(&self) -> u32 {
         self.queue_index
     }
+    /// The squads and entities the command was issued to.
+    pub fn selection(&self) -> &Selection {
+        &self.selection
+    }
 }
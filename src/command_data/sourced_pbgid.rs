@@ -1,24 +1,38 @@
+use crate::command_data::{Selection, Target};
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-/// A command format with both an entity pbgid and a source identifier.
+/// A command format with both an entity pbgid and a source identifier, along with the target of
+/// entity abilities.
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SourcedPbgid {
     tick: u32,
     index: u32,
     pbgid: u32,
     source_identifier: u16,
+    target: Option<Target>,
+    selection: Selection,
 }
 
 impl SourcedPbgid {
-    pub(crate) fn new(tick: u32, index: u32, pbgid: u32, source_identifier: u16) -> Self {
+    pub(crate) fn new(
+        tick: u32,
+        index: u32,
+        pbgid: u32,
+        source_identifier: u16,
+        target: Option<Target>,
+        selection: Selection,
+    ) -> Self {
         Self {
             tick,
             index,
             pbgid,
             source_identifier,
+            target,
+            selection,
         }
     }
 
@@ -48,4 +62,13 @@ impl SourcedPbgid {
     pub fn source_identifier(&self) -> u16 {
         self.source_identifier
     }
+    /// The position, entity or squad an entity ability was aimed at. This is `None` for abilities
+    /// that don't need a target and for build commands.
+    pub fn target(&self) -> Option<Target> {
+        self.target
+    }
+    /// The squads and entities the command was issued to.
+    pub fn selection(&self) -> &Selection {
+        &self.selection
+    }
 }
@@ -0,0 +1,60 @@
+use crate::command_data::{Selection, Target};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A command format issued to a producing unit or building that contains a target, such as the
+/// position or entity its rally point was set to.
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SourcedTarget {
+    tick: u32,
+    index: u32,
+    target: Target,
+    selection: Selection,
+}
+
+impl SourcedTarget {
+    pub(crate) fn new(tick: u32, index: u32, target: Target, selection: Selection) -> Self {
+        Self {
+            tick,
+            index,
+            target,
+            selection,
+        }
+    }
+
+    /// This value is the tick at which the command was found while parsing the replay, which
+    /// represents the time in the replay at which it was executed. Because CoH3's engine runs at 8
+    /// ticks per second, you can divide this value by 8 to get the number of seconds since the
+    /// replay began, which will tell you when this command was executed.
+    pub fn tick(&self) -> u32 {
+        self.tick
+    }
+    /// This value is the index of the command relative to the player who issued the command.
+    /// Indexes start at 1 and increment on every player-issued command, which means you should be
+    /// able to look at the maximum index value of the commands associated with a player to
+    /// determine how many commands that player issued in a given game.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+    /// The internal identifier given by the game engine to the building the command was issued
+    /// to, or to the squad for squad commands. If several were selected, this is the first of
+    /// them; see `selection` for the full list.
+    pub fn source_identifier(&self) -> Option<u32> {
+        self.selection
+            .entities()
+            .first()
+            .or_else(|| self.selection.squads().first())
+            .copied()
+    }
+    /// The position, entity or squad the command was directed at.
+    pub fn target(&self) -> Target {
+        self.target
+    }
+    /// The squads and entities the command was issued to.
+    pub fn selection(&self) -> &Selection {
+        &self.selection
+    }
+}
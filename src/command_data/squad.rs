@@ -0,0 +1,52 @@
+use crate::command_data::{Selection, Target};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A command format issued to a selection of squads or entities that may optionally contain a
+/// target, such as the direction a team weapon should be set up facing or the building to repair.
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Squad {
+    tick: u32,
+    index: u32,
+    target: Option<Target>,
+    selection: Selection,
+}
+
+impl Squad {
+    pub(crate) fn new(tick: u32, index: u32, target: Option<Target>, selection: Selection) -> Self {
+        Self {
+            tick,
+            index,
+            target,
+            selection,
+        }
+    }
+
+    /// This value is the tick at which the command was found while parsing the replay, which
+    /// represents the time in the replay at which it was executed. Because CoH3's engine runs at 8
+    /// ticks per second, you can divide this value by 8 to get the number of seconds since the
+    /// replay began, which will tell you when this command was executed.
+    pub fn tick(&self) -> u32 {
+        self.tick
+    }
+    /// This value is the index of the command relative to the player who issued the command.
+    /// Indexes start at 1 and increment on every player-issued command, which means you should be
+    /// able to look at the maximum index value of the commands associated with a player to
+    /// determine how many commands that player issued in a given game.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+    /// The position, entity, squad or direction the command was directed at, or `None` if the
+    /// command didn't include a target.
+    pub fn target(&self) -> Option<Target> {
+        self.target
+    }
+    /// The squads and entities the command was issued to. Always empty for player commands, such
+    /// as detonating charges.
+    pub fn selection(&self) -> &Selection {
+        &self.selection
+    }
+}
@@ -0,0 +1,53 @@
+use crate::command_data::Selection;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A command format issued to a selection of squads that toggles or sets a piece of squad state,
+/// such as a combat stance or preferred weapon. No sample replay contains these commands, so the
+/// layout of the new state is unknown and it isn't decoded; see `SquadState::argument`.
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SquadState {
+    tick: u32,
+    index: u32,
+    argument: Vec<u8>,
+    selection: Selection,
+}
+
+impl SquadState {
+    pub(crate) fn new(tick: u32, index: u32, argument: Vec<u8>, selection: Selection) -> Self {
+        Self {
+            tick,
+            index,
+            argument,
+            selection,
+        }
+    }
+
+    /// This value is the tick at which the command was found while parsing the replay, which
+    /// represents the time in the replay at which it was executed. Because CoH3's engine runs at 8
+    /// ticks per second, you can divide this value by 8 to get the number of seconds since the
+    /// replay began, which will tell you when this command was executed.
+    pub fn tick(&self) -> u32 {
+        self.tick
+    }
+    /// This value is the index of the command relative to the player who issued the command.
+    /// Indexes start at 1 and increment on every player-issued command, which means you should be
+    /// able to look at the maximum index value of the commands associated with a player to
+    /// determine how many commands that player issued in a given game.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+    /// The undecoded bytes following the selection. These are expected to hold the new state set
+    /// by the command, such as the stance, weapon slot or move type selected, but their layout
+    /// hasn't been verified against a real replay.
+    pub fn argument(&self) -> &[u8] {
+        &self.argument
+    }
+    /// The squads and entities the command was issued to.
+    pub fn selection(&self) -> &Selection {
+        &self.selection
+    }
+}
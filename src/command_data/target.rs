@@ -0,0 +1,70 @@
+use crate::command_data::Position;
+use crate::data::{ParserResult, Span};
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take},
+    combinator::map,
+    number::complete::{le_u32, le_u8},
+    sequence::{preceded, tuple},
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Squads referenced as targets are numbered this much higher than the same squads in selections.
+const SQUAD_TARGET_OFFSET: u32 = 50000;
+
+/// The target of a command, such as the point a squad was ordered to move to or the strategic
+/// point it was ordered to capture.
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Target {
+    /// A position in world coordinates.
+    Position(Position),
+    /// The internal identifier given by the game engine to the targeted entity.
+    Entity(u32),
+    /// The internal identifier given by the game engine to the targeted squad. Squad targets are
+    /// numbered 50000 higher than the same squad in `Selection::squads`; use `Target::squad` to
+    /// compare them.
+    Squad(u32),
+    /// A direction in world coordinates, stored as a unit vector. Used by commands that turn units
+    /// to face a given way.
+    Direction(Position),
+}
+
+impl Target {
+    /// The targeted entity's identifier, or `None` if the target isn't an entity.
+    pub fn entity(&self) -> Option<u32> {
+        match self {
+            Target::Entity(id) => Some(*id),
+            _ => None,
+        }
+    }
+    /// The targeted squad's identifier in the same numbering as `Selection::squads`, or `None` if
+    /// the target isn't a squad.
+    pub fn squad(&self) -> Option<u32> {
+        match self {
+            Target::Squad(id) => id.checked_sub(SQUAD_TARGET_OFFSET),
+            _ => None,
+        }
+    }
+
+    /// Target arguments are prefixed with a kind byte of `0x01` and a length byte, followed by a
+    /// tag identifying the kind of target.
+    pub(crate) fn parse_argument(input: Span) -> ParserResult<Target> {
+        preceded(tuple((tag([0x01]), le_u8)), Self::parse)(input)
+    }
+
+    pub(crate) fn parse(input: Span) -> ParserResult<Target> {
+        alt((
+            map(preceded(tag([0x02]), Position::parse), Target::Position),
+            map(preceded(tag([0x03]), le_u32), Target::Entity),
+            map(preceded(tag([0x04]), le_u32), Target::Squad),
+            map(
+                preceded(tag([0x05]), tuple((Position::parse, take(4u32)))),
+                |(direction, _)| Target::Direction(direction),
+            ),
+        ))(input)
+    }
+}
@@ -1,3 +1,4 @@
+use crate::command_data::Selection;
 use crate::command_type::CommandType;
 
 #[cfg(feature = "serde")]
@@ -5,20 +6,27 @@ use serde::{Deserialize, Serialize};
 
 /// A catch-all command format to cover commands that aren't currently being parsed.
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Unknown {
     tick: u32,
     index: u32,
     action_type: CommandType,
+    selection: Selection,
 }
 
 impl Unknown {
-    pub(crate) fn new(tick: u32, index: u32, action_type: CommandType) -> Self {
+    pub(crate) fn new(
+        tick: u32,
+        index: u32,
+        action_type: CommandType,
+        selection: Selection,
+    ) -> Self {
         Self {
             tick,
             index,
             action_type,
+            selection,
         }
     }
 
@@ -42,4 +50,8 @@ impl Unknown {
     pub fn action_type(&self) -> CommandType {
         self.action_type
     }
+    /// The squads and entities the command was issued to.
+    pub fn selection(&self) -> &Selection {
+        &self.selection
+    }
 }
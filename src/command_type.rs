@@ -187,6 +187,23 @@ impl CommandType {
     pub(crate) fn parse(input: Span) -> ParserResult<CommandType> {
         map(le_u8, CommandType::from)(input)
     }
+
+    /// Whether this is an entity (`CMD_*`), squad (`SCMD_*`) or formation (`FCMD_*`) command,
+    /// which are issued to a selection of units.
+    pub(crate) fn is_unit_command(&self) -> bool {
+        u8::from(*self) < u8::from(CommandType::FCMD_COUNT)
+    }
+
+    /// Whether this is one of the developer cheat commands.
+    pub fn is_cheat(&self) -> bool {
+        matches!(
+            self,
+            CommandType::PCMD_CheatBuildTime
+                | CommandType::PCMD_CheatIgnoreCosts
+                | CommandType::PCMD_CheatResources
+                | CommandType::PCMD_CheatRevealAll
+        )
+    }
 }
 
 impl From<u8> for CommandType {
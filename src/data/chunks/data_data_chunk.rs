@@ -32,7 +32,7 @@ impl Option {
 
 #[derive(Debug)]
 pub struct DataDataChunk {
-    _header: Header,
+    pub header: Header,
     _opponent_type: u32,
     pub players: Vec<Player>,
     pub skirmish: bool,
@@ -77,7 +77,7 @@ impl DataDataChunk {
                     (mod_uuid, unknown_number),
                 )| {
                     DataData(DataDataChunk {
-                        _header: header.clone(),
+                        header: header.clone(),
                         _opponent_type: opponent_type,
                         players,
                         skirmish,
@@ -1,5 +1,5 @@
-use crate::data::parser::parse_utf8_fixed;
-use crate::data::{ParserResult, Span};
+use crate::data::parser::{located, parse_utf8_fixed};
+use crate::data::{Location, ParserResult, Span};
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::combinator::{cut, map};
@@ -13,10 +13,18 @@ pub struct Header {
     pub chunk_type: String,
     pub version: u32,
     pub length: u32,
+    pub length_location: Location,
     _name_length: u32,
 }
 
 impl Header {
+    pub fn data_location(&self) -> Location {
+        Location {
+            offset: self.length_location.end() + 4,
+            length: self.length as usize,
+        }
+    }
+
     #[tracable_parser]
     pub fn parse(input: Span) -> ParserResult<Header> {
         map(
@@ -25,15 +33,16 @@ impl Header {
                 cut(tuple((
                     Self::parse_chunk_type,
                     Self::parse_version,
-                    Self::parse_length,
+                    located(Self::parse_length),
                     Self::parse_name_length,
                 ))),
             )),
-            |(chunk_kind, (chunk_type, version, length, name_length))| Header {
+            |(chunk_kind, (chunk_type, version, (length, length_location), name_length))| Header {
                 chunk_kind,
                 chunk_type,
                 version,
                 length,
+                length_location,
                 _name_length: name_length,
             },
         )(input)
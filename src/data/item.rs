@@ -1,17 +1,33 @@
-use crate::data::{ParserResult, Span};
+use crate::data::parser::{located, parse_utf8_variable};
+use crate::data::{Location, ParserResult, Span};
 use nom::bytes::complete::take;
 use nom::combinator::{cut, map, peek};
 use nom::multi::length_data;
-use nom::number::complete::le_u32;
+use nom::number::complete::{le_u32, le_u64, le_u8};
 use nom::sequence::tuple;
 
 #[derive(Debug)]
 pub struct Item {
-    _data: Vec<u8>,
+    pub iteminstance_id: u64,
+    pub itemdefinition_id: u64,
+    pub profile_id: u64,
+    pub profile_id_location: Location,
+    pub durability: u32,
+    pub durability_type: u32,
+    pub metadata: String,
+    pub itemlocation_id: u64,
+}
+
+/// An item record as stored in the replay. Records whose payload can't be decoded keep `item` as
+/// `None`, but still locate the profile ID copy inside them so it can be anonymized.
+#[derive(Debug)]
+pub struct ItemRecord {
+    pub item: Option<Item>,
+    pub profile_id_location: Option<Location>,
 }
 
 impl Item {
-    pub fn parse_item(input: Span) -> ParserResult<Item> {
+    pub fn parse_item(input: Span) -> ParserResult<ItemRecord> {
         cut(map(
             tuple((
                 take(4u32),
@@ -19,8 +35,16 @@ impl Item {
                 take(20u32),
                 length_data(le_u32),
             )),
-            |(_, _, _, data)| Item {
-                _data: data.to_vec(),
+            |(_, _, _, data)| {
+                let item = Self::parse_data(data).ok().map(|(_, item)| item);
+
+                ItemRecord {
+                    profile_id_location: item
+                        .as_ref()
+                        .map(|item| item.profile_id_location)
+                        .or_else(|| Self::profile_id_location(&data)),
+                    item,
+                }
             },
         ))(input)
     }
@@ -42,6 +66,56 @@ impl Item {
         }
     }
 
+    fn parse_data(input: Span) -> ParserResult<Item> {
+        map(
+            tuple((
+                le_u64,
+                take(8u32),
+                le_u64,
+                located(le_u64),
+                le_u32,
+                le_u32,
+                le_u8,
+                parse_utf8_variable(le_u32),
+                take(8u32),
+                le_u64,
+            )),
+            |(
+                iteminstance_id,
+                _,
+                itemdefinition_id,
+                (profile_id, profile_id_location),
+                durability,
+                durability_type,
+                _,
+                (_, metadata),
+                _,
+                itemlocation_id,
+            )| Item {
+                iteminstance_id,
+                itemdefinition_id,
+                profile_id,
+                profile_id_location,
+                durability,
+                durability_type,
+                metadata,
+                itemlocation_id,
+            },
+        )(input)
+    }
+
+    // fallback for payloads that fail to decode, matching the offset read by parse_data
+    fn profile_id_location(data: &Span) -> Option<Location> {
+        if data.len() < 32 {
+            return None;
+        }
+
+        Some(Location {
+            offset: data.location_offset() + 24,
+            length: 8,
+        })
+    }
+
     fn parse_sublength(input: Span) -> ParserResult<Span> {
         let (input, id) = peek(le_u32)(input)?;
 
@@ -13,10 +13,22 @@ pub mod ticks;
 
 use crate::data::chunky::Chunky;
 use crate::data::header::Header;
-use crate::data::item::Item;
+pub use crate::data::item::{Item, ItemRecord};
 pub use crate::data::player::Player;
 pub use crate::data::replay::Replay;
 
 pub type Span<'a> = LocatedSpan<&'a [u8], TracableInfo>;
 
 pub type ParserResult<'a, T> = IResult<Span<'a>, T>;
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Location {
+    pub offset: usize,
+    pub length: usize,
+}
+
+impl Location {
+    pub fn end(&self) -> usize {
+        self.offset + self.length
+    }
+}
@@ -1,4 +1,4 @@
-use crate::data::Span;
+use crate::data::{Location, Span};
 use byteorder::{LittleEndian, ReadBytesExt};
 use nom::bytes::complete::{take, take_while};
 use nom::combinator::{map, peek, verify};
@@ -93,3 +93,17 @@ where
 pub fn take_zeroes(input: Span) -> IResult<Span, Span> {
     take_while(|n: u8| n == 0)(input)
 }
+
+pub fn located<'a, O, E, F>(mut f: F) -> impl FnMut(Span<'a>) -> IResult<Span<'a>, (O, Location), E>
+where
+    E: ParseError<Span<'a>>,
+    F: FnMut(Span<'a>) -> IResult<Span<'a>, O, E>,
+{
+    move |input: Span<'a>| {
+        let offset = input.location_offset();
+        let (input, res) = f(input)?;
+        let length = input.location_offset() - offset;
+
+        Ok((input, (res, Location { offset, length })))
+    }
+}
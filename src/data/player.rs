@@ -1,6 +1,6 @@
-use crate::data::parser::{parse_utf16_variable, parse_utf8_variable};
-use crate::data::Item;
-use crate::data::{ParserResult, Span};
+use crate::data::parser::{located, parse_utf16_variable, parse_utf8_variable};
+use crate::data::{Item, ItemRecord};
+use crate::data::{Location, ParserResult, Span};
 use nom::bytes::complete::take;
 use nom::combinator::{cut, map};
 use nom::multi::many_m_n;
@@ -14,12 +14,15 @@ pub struct Player {
     pub id: u32,
     pub human: u8,
     pub name: String,
+    pub name_location: Location,
     pub team: u32,
     pub faction: String,
-    _ai_type: String,
+    pub ai_type: String,
     pub steam_id: String,
+    pub steam_id_location: Location,
     pub profile_id: u64,
-    _items: Vec<Item>,
+    pub profile_id_location: Location,
+    pub items: Vec<ItemRecord>,
 }
 
 impl Player {
@@ -28,7 +31,7 @@ impl Player {
             let (input, player) = cut(map(
                 tuple((
                     le_u8,
-                    Self::parse_name,
+                    located(Self::parse_name),
                     Self::parse_team,
                     le_u32,
                     take(1u32),
@@ -36,35 +39,46 @@ impl Player {
                     take(8u32),
                     Self::parse_ai,
                     take(40u32),
-                    le_u64,
+                    located(le_u64),
                     take(1u32),
-                    Self::parse_steam_id,
+                    located(Self::parse_steam_id),
                     take(18u32),
                 )),
-                |(human, name, team, id, _, faction, _, ai_type, _, profile_id, _, steam_id, _)| {
+                |(
+                    human,
+                    (name, name_location),
+                    team,
+                    id,
+                    _,
+                    faction,
+                    _,
+                    ai_type,
+                    _,
+                    (profile_id, profile_id_location),
+                    _,
+                    (steam_id, steam_id_location),
+                    _,
+                )| {
                     Player {
                         id,
                         human,
                         name,
+                        name_location,
                         team,
                         faction,
-                        _ai_type: ai_type,
+                        ai_type,
                         steam_id,
+                        steam_id_location,
                         profile_id,
-                        _items: vec![],
+                        profile_id_location,
+                        items: vec![],
                     }
                 },
             ))(input)?;
 
             let (input, items) = Self::parse_items(input, &player, version)?;
             let (input, _) = take(4u32)(input)?;
-            Ok((
-                input,
-                Player {
-                    _items: items,
-                    ..player
-                },
-            ))
+            Ok((input, Player { items, ..player }))
         }
     }
 
@@ -97,7 +111,7 @@ impl Player {
         input: Span<'a>,
         player: &Player,
         version: u16,
-    ) -> IResult<Span<'a>, Vec<Item>> {
+    ) -> IResult<Span<'a>, Vec<ItemRecord>> {
         if player.human == 0 {
             let (input, _) = take(44u32)(input)?;
             return Ok((input, vec![]));
@@ -7,6 +7,7 @@ use crate::data::{ParserResult, Span};
 use crate::Message;
 use nom::combinator::eof;
 use nom::combinator::map;
+use nom::combinator::rest;
 use nom::multi::many_till;
 use nom::sequence::tuple;
 use nom_tracable::tracable_parser;
@@ -21,11 +22,26 @@ pub struct Replay {
     _chunkies: Vec<Chunky>,
     pub chunks: Vec<Chunk>,
     pub ticks: Vec<Tick>,
+    pub truncated_at: Option<usize>,
 }
 
 impl Replay {
+    #[tracable_parser]
+    pub fn parse_version(input: Span) -> ParserResult<u16> {
+        map(Header::parse_header, |header| header.version)(input)
+    }
+
     #[tracable_parser]
     pub fn from_span(input: Span) -> ParserResult<Replay> {
+        Self::parse(input, false)
+    }
+
+    #[tracable_parser]
+    pub fn from_span_lenient(input: Span) -> ParserResult<Replay> {
+        Self::parse(input, true)
+    }
+
+    fn parse(input: Span, lenient: bool) -> ParserResult<Replay> {
         let (input, header) = Header::parse_header(input)?;
 
         let mut parser = map(
@@ -35,7 +51,7 @@ impl Replay {
                 Chunky::parse,
                 Chunk::parse(header.version),
                 Chunk::parse(header.version),
-                many_till(Tick::parse, eof),
+                |input| Self::parse_ticks(input, lenient),
             )),
             |(
                 first_chunky,
@@ -43,13 +59,14 @@ impl Replay {
                 second_chunky,
                 foldinfo_chunk,
                 datasdsc_chunk,
-                (ticks, _),
+                (ticks, truncated_at),
             )| {
                 Replay {
                     header: header.clone(),
                     _chunkies: vec![first_chunky, second_chunky],
                     chunks: vec![foldpost_chunk, foldinfo_chunk, datasdsc_chunk],
                     ticks,
+                    truncated_at,
                 }
             },
         );
@@ -57,6 +74,31 @@ impl Replay {
         parser(input)
     }
 
+    fn parse_ticks(input: Span, lenient: bool) -> ParserResult<(Vec<Tick>, Option<usize>)> {
+        if !lenient {
+            return map(many_till(Tick::parse, eof), |(ticks, _)| (ticks, None))(input);
+        }
+
+        let mut input = input;
+        let mut ticks = Vec::new();
+
+        while !input.fragment().is_empty() {
+            match Tick::parse(input) {
+                Ok((remaining, tick)) => {
+                    ticks.push(tick);
+                    input = remaining;
+                }
+                Err(_) => {
+                    let offset = input.location_offset();
+                    let (input, _) = rest(input)?;
+                    return Ok((input, (ticks, Some(offset))));
+                }
+            }
+        }
+
+        Ok((input, (ticks, None)))
+    }
+
     pub fn data_chunks(&self) -> Vec<&Chunk> {
         self.chunks
             .iter()
@@ -114,13 +156,15 @@ impl Replay {
         })
     }
 
-    pub fn commands(&self) -> HashMap<u32, Vec<Command>> {
+    /// Commands whose player ID couldn't be read are grouped under `None`.
+    pub fn commands(&self) -> HashMap<Option<u32>, Vec<Command>> {
         self.command_ticks()
             .enumerate()
             .fold(HashMap::new(), |mut acc, (idx, tick)| {
                 for bundle in &tick.bundles {
                     for command in &bundle.commands {
-                        let player_commands = acc.entry(command.player_id as u32).or_default();
+                        let player_commands =
+                            acc.entry(command.player_id.map(u32::from)).or_default();
                         player_commands.push(Command::from_data_command_at_tick(
                             command.clone(),
                             idx as u32 + 1,
@@ -138,9 +182,13 @@ impl Replay {
             .fold(HashMap::new(), |mut acc, (idx, tick)| {
                 for bundle in &tick.bundles {
                     for command in &bundle.commands {
-                        let player_commands = acc.entry(command.player_id as u32).or_default();
+                        let Some(player_id) = command.player_id else {
+                            continue;
+                        };
+                        let player_commands = acc.entry(player_id as u32).or_default();
                         player_commands.push(RawCommand::from_data_command_at_tick(
                             command.clone(),
+                            player_id,
                             idx as u32 + 1,
                         ));
                     }
@@ -149,7 +197,7 @@ impl Replay {
             })
     }
 
-    pub fn messages(&self) -> HashMap<String, Vec<Message>> {
+    pub fn messages(&self) -> HashMap<u64, Vec<Message>> {
         self.ticks
             .iter()
             .enumerate()
@@ -159,8 +207,13 @@ impl Replay {
             })
             .fold(HashMap::new(), |mut acc, (tick, messages)| {
                 for message in messages.iter() {
-                    let msgs = acc.entry(message.name.clone()).or_default();
-                    msgs.push(Message::new(tick as u32, message.message.clone()));
+                    let msgs = acc.entry(message.sender_id).or_default();
+                    msgs.push(Message::new(
+                        tick as u32,
+                        message.message.clone(),
+                        message.channel,
+                        message.sender_id,
+                    ));
                 }
                 acc
             })
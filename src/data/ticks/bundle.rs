@@ -2,7 +2,8 @@ use super::Command;
 use crate::data::{ParserResult, Span};
 use nom::bytes::complete::take;
 use nom::combinator::map;
-use nom::multi::{length_value, many1};
+use nom::error::{Error, ErrorKind};
+use nom::multi::length_value;
 use nom::number::complete::le_u32;
 use nom::sequence::tuple;
 use nom_tracable::tracable_parser;
@@ -20,7 +21,7 @@ impl Bundle {
             tuple((
                 le_u32,
                 take(4u32),
-                length_value(le_u32, many1(Command::parse)),
+                length_value(le_u32, Self::parse_commands),
             )),
             |(index, _, commands)| Bundle {
                 _index: index,
@@ -28,4 +29,30 @@ impl Bundle {
             },
         )(input)
     }
+
+    /// Bundles hold at least one command. Commands that can't be framed are salvaged as malformed
+    /// rather than failing the bundle.
+    fn parse_commands(mut input: Span) -> ParserResult<Vec<Command>> {
+        if input.fragment().is_empty() {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::Many1)));
+        }
+
+        let mut commands = Vec::new();
+
+        while !input.fragment().is_empty() {
+            let (remaining, command) = match Command::parse(input) {
+                Ok((remaining, command))
+                    if remaining.location_offset() > input.location_offset() =>
+                {
+                    (remaining, command)
+                }
+                _ => Command::parse_malformed(input)?,
+            };
+
+            commands.push(command);
+            input = remaining;
+        }
+
+        Ok((input, commands))
+    }
 }
@@ -1,31 +1,104 @@
 use crate::{
+    command_data::{Position, Target},
     command_type::CommandType,
     data::{ParserResult, Span},
 };
 use nom::{
-    bytes::complete::take,
-    combinator::{flat_map, map, peek, rest},
-    multi::length_value,
-    number::complete::{le_u16, le_u32, le_u8},
-    sequence::tuple,
+    branch::alt,
+    bytes::complete::{tag, take},
+    combinator::{flat_map, map, opt, peek, rest},
+    multi::{count, length_count, length_value},
+    number::complete::{be_u32, le_u16, le_u32, le_u8},
+    sequence::{preceded, tuple},
 };
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub enum CommandData {
+    Ability(Option<u32>, Option<Target>),
+    Cheat(Vec<u8>),
     Pbgid(u32),
+    Placement(u32, Position, Position, Position, Vec<u32>),
+    SourcedAbility(u16, Option<u32>, Option<Target>),
     SourcedPbgid(u32, u16),
     Sourced(u16),
     SourcedIndex(u16, u32),
+    SourcedTarget(Target),
+    Squad(Option<Target>),
+    SquadPbgid(u32),
+    SquadState(Vec<u8>),
+    SquadTarget(Target),
     Unknown,
+    Malformed(String),
 }
 
 impl CommandData {
+    pub fn parse_ability(input: Span) -> ParserResult<CommandData> {
+        map(
+            preceded(Self::parse_selection_prefix, Self::parse_ability_argument),
+            |(pbgid, target)| CommandData::Ability(pbgid, target),
+        )(input)
+    }
+
+    /// The layout of cheat commands is unknown, so their data is kept whole.
+    pub fn parse_cheat(input: Span) -> ParserResult<CommandData> {
+        map(rest, |argument: Span| CommandData::Cheat(argument.to_vec()))(input)
+    }
+
+    pub fn parse_player_ability(input: Span) -> ParserResult<CommandData> {
+        map(
+            preceded(take(24u32), Self::parse_ability_argument),
+            |(pbgid, target)| CommandData::Ability(pbgid, target),
+        )(input)
+    }
+
+    pub fn parse_player_squad(input: Span) -> ParserResult<CommandData> {
+        map(
+            preceded(take(24u32), opt(Target::parse_argument)),
+            CommandData::Squad,
+        )(input)
+    }
+
     pub fn parse_pbgid(input: Span) -> ParserResult<CommandData> {
         map(tuple((take(27u32), le_u32)), |(_, pbgid)| {
             CommandData::Pbgid(pbgid)
         })(input)
     }
 
+    /// Placement arguments hold the structure's pbgid, the start and end of the placement, a point
+    /// in the direction the structure faces, 7 bytes of unknown data and a length-prefixed list of
+    /// builder squad IDs.
+    pub fn parse_placement(input: Span) -> ParserResult<CommandData> {
+        map(
+            preceded(
+                take(27u32),
+                tuple((
+                    le_u32,
+                    Position::parse,
+                    Position::parse,
+                    Position::parse,
+                    preceded(take(7u32), length_count(le_u8, le_u32)),
+                )),
+            ),
+            |(pbgid, start, end, facing, builders)| {
+                CommandData::Placement(pbgid, start, end, facing, builders)
+            },
+        )(input)
+    }
+
+    /// Entity abilities hold the caster's source identifier within the 20 byte prefix, followed
+    /// by the selection and the ability argument.
+    pub fn parse_sourced_ability(input: Span) -> ParserResult<CommandData> {
+        map(
+            tuple((
+                peek(preceded(take(22u32), le_u16)),
+                preceded(Self::parse_selection_prefix, Self::parse_ability_argument),
+            )),
+            |(source_identifier, (pbgid, target))| {
+                CommandData::SourcedAbility(source_identifier, pbgid, target)
+            },
+        )(input)
+    }
+
     pub fn parse_sourced_pbgid(input: Span) -> ParserResult<CommandData> {
         map(
             tuple((take(22u32), le_u16, take(3u32), le_u32)),
@@ -48,22 +121,186 @@ impl CommandData {
         )(input)
     }
 
+    pub fn parse_sourced_target(input: Span) -> ParserResult<CommandData> {
+        map(
+            preceded(Self::parse_selection_prefix, Target::parse_argument),
+            CommandData::SourcedTarget,
+        )(input)
+    }
+
+    pub fn parse_squad(input: Span) -> ParserResult<CommandData> {
+        map(
+            preceded(Self::parse_selection_prefix, opt(Target::parse_argument)),
+            CommandData::Squad,
+        )(input)
+    }
+
+    pub fn parse_squad_pbgid(input: Span) -> ParserResult<CommandData> {
+        map(
+            preceded(Self::parse_selection_prefix, Self::parse_pbgid_argument),
+            CommandData::SquadPbgid,
+        )(input)
+    }
+
+    pub fn parse_squad_state(input: Span) -> ParserResult<CommandData> {
+        map(
+            preceded(Self::parse_selection_prefix, rest),
+            |argument: Span| CommandData::SquadState(argument.to_vec()),
+        )(input)
+    }
+
+    pub fn parse_squad_target(input: Span) -> ParserResult<CommandData> {
+        map(
+            preceded(Self::parse_selection_prefix, Target::parse_argument),
+            CommandData::SquadTarget,
+        )(input)
+    }
+
     pub fn parse_unknown(input: Span) -> ParserResult<CommandData> {
         map(rest, |_| CommandData::Unknown)(input)
     }
 
+    /// Entity and squad commands store the IDs of the selected units 20 bytes into the command
+    /// data. A single ID is stored big endian; multiple IDs are preceded by a `0x4N` tag, where `N`
+    /// is the number of IDs, and stored little endian. The high nibble of each ID identifies
+    /// whether it refers to an entity or a squad.
+    fn parse_selection(input: Span) -> ParserResult<Vec<u32>> {
+        let (input, tag) = peek(le_u8)(input)?;
+
+        if tag >> 4 == 0x4 {
+            preceded(le_u8, count(le_u32, (tag & 0xf) as usize))(input)
+        } else {
+            map(be_u32, |id| vec![id])(input)
+        }
+    }
+
+    /// Skips the 20 bytes common to every entity and squad command and parses the selection that
+    /// follows them.
+    pub fn parse_selection_prefix(input: Span) -> ParserResult<Vec<u32>> {
+        preceded(take(20u32), Self::parse_selection)(input)
+    }
+
+    /// Arguments following the selection are prefixed with a kind and length byte. Pbgid
+    /// arguments hold a flag byte followed by the pbgid itself.
+    fn parse_pbgid_argument(input: Span) -> ParserResult<u32> {
+        preceded(take(3u32), le_u32)(input)
+    }
+
+    fn parse_ability_argument(input: Span) -> ParserResult<(Option<u32>, Option<Target>)> {
+        map(
+            opt(alt((
+                map(Target::parse_argument, |target| (None, Some(target))),
+                map(
+                    preceded(tag([0x23]), Self::parse_pbgid_argument_data),
+                    |pbgid| (Some(pbgid), None),
+                ),
+                map(
+                    preceded(
+                        tag([0x24]),
+                        tuple((
+                            Self::parse_pbgid_argument_data,
+                            preceded(take(6u32), Target::parse),
+                        )),
+                    ),
+                    |(pbgid, target)| (Some(pbgid), Some(target)),
+                ),
+                map(
+                    preceded(
+                        tag([0x29]),
+                        tuple((
+                            Self::parse_pbgid_argument_data,
+                            preceded(take(1u32), Target::parse),
+                        )),
+                    ),
+                    |(pbgid, target)| (Some(pbgid), Some(target)),
+                ),
+            ))),
+            Option::unwrap_or_default,
+        )(input)
+    }
+
+    /// Reads the length byte and flag byte of a pbgid argument whose kind has already been read,
+    /// followed by the pbgid itself.
+    fn parse_pbgid_argument_data(input: Span) -> ParserResult<u32> {
+        preceded(take(2u32), le_u32)(input)
+    }
+
     pub fn parser_for_type(
         command_type: CommandType,
     ) -> impl FnMut(Span) -> ParserResult<CommandData> {
         match command_type {
-            CommandType::PCMD_Ability
-            | CommandType::PCMD_InstantUpgrade
+            CommandType::SCMD_Ability => Self::parse_ability,
+            CommandType::PCMD_Ability => Self::parse_player_ability,
+            CommandType::PCMD_CheatBuildTime
+            | CommandType::PCMD_CheatIgnoreCosts
+            | CommandType::PCMD_CheatResources
+            | CommandType::PCMD_CheatRevealAll => Self::parse_cheat,
+            CommandType::PCMD_InstantUpgrade
+            | CommandType::PCMD_StopAbility
             | CommandType::PCMD_TentativeUpgrade => Self::parse_pbgid,
-            CommandType::CMD_BuildSquad | CommandType::CMD_Ability | CommandType::CMD_Upgrade => {
-                Self::parse_sourced_pbgid
-            }
+            CommandType::PCMD_PlaceAndConstructEntities => Self::parse_placement,
+            CommandType::PCMD_DetonateCharges
+            | CommandType::PCMD_FormationSquadGroupAddSquad
+            | CommandType::PCMD_FormationSquadGroupCreateBegin
+            | CommandType::PCMD_FormationSquadGroupCreateEnd => Self::parse_player_squad,
+            CommandType::CMD_Ability => Self::parse_sourced_ability,
+            CommandType::CMD_BuildSquad | CommandType::CMD_Upgrade => Self::parse_sourced_pbgid,
             CommandType::CMD_CancelConstruction => Self::parse_sourced,
             CommandType::CMD_CancelProduction => Self::parse_sourced_index,
+            CommandType::CMD_RallyPoint | CommandType::SCMD_RallyPoint => {
+                Self::parse_sourced_target
+            }
+            CommandType::CMD_StopAbility
+            | CommandType::SCMD_InstantReinforceUnit
+            | CommandType::SCMD_InstantUpgrade
+            | CommandType::SCMD_ReinforceUnit
+            | CommandType::SCMD_StopAbility
+            | CommandType::SCMD_Upgrade => Self::parse_squad_pbgid,
+            CommandType::CMD_BuildStructure
+            | CommandType::CMD_Capture
+            | CommandType::SCMD_BuildStructure
+            | CommandType::SCMD_Capture
+            | CommandType::CMD_InstantLoad
+            | CommandType::CMD_Load
+            | CommandType::SCMD_CaptureTeamWeapon
+            | CommandType::SCMD_InstantLoad
+            | CommandType::SCMD_Load
+            | CommandType::SCMD_Recrew => Self::parse_squad_target,
+            CommandType::CMD_CancelRestoreWreck
+            | CommandType::CMD_DefuseMine
+            | CommandType::CMD_Destroy
+            | CommandType::CMD_Evacuate
+            | CommandType::CMD_PlaceCharge
+            | CommandType::CMD_Repair
+            | CommandType::CMD_RestoreWreck
+            | CommandType::CMD_Stop
+            | CommandType::CMD_Teardown
+            | CommandType::CMD_Unload
+            | CommandType::CMD_UnloadSquads
+            | CommandType::SCMD_AbandonTeamWeapon
+            | CommandType::SCMD_CancelQueuedCommand
+            | CommandType::SCMD_DefuseCharge
+            | CommandType::SCMD_DefuseMine
+            | CommandType::SCMD_Destroy
+            | CommandType::SCMD_Evacuate
+            | CommandType::SCMD_InstantSetupTeamWeapon
+            | CommandType::SCMD_JoinFormationSquadGroup
+            | CommandType::SCMD_PlaceCharge
+            | CommandType::SCMD_SetupTeamWeapon
+            | CommandType::SCMD_Stop
+            | CommandType::SCMD_Unload
+            | CommandType::SCMD_UnloadSquads
+            | CommandType::FCMD_FormationSquadGroupAttack
+            | CommandType::FCMD_FormationSquadGroupAttackMove
+            | CommandType::FCMD_FormationSquadGroupMove
+            | CommandType::FCMD_FormationSquadGroupStop => Self::parse_squad,
+            CommandType::CMD_HoldPositionOff
+            | CommandType::CMD_HoldPositionOn
+            | CommandType::SCMD_CombatStance
+            | CommandType::SCMD_HoldPosition
+            | CommandType::SCMD_SetMoveType
+            | CommandType::SCMD_Surprise
+            | CommandType::SCMD_WeaponPreference => Self::parse_squad_state,
             _ => Self::parse_unknown,
         }
     }
@@ -72,10 +309,11 @@ impl CommandData {
 #[derive(Debug, Clone)]
 pub struct Command {
     pub action_type: CommandType,
-    pub player_id: u8,
+    // `None` when a malformed command is too short to hold a player ID
+    pub player_id: Option<u8>,
     pub index: u32,
     pub data: CommandData,
-    #[cfg(feature = "raw")]
+    pub selection: Vec<u32>,
     pub bytes: Vec<u8>,
 }
 
@@ -90,41 +328,91 @@ impl Command {
         )(input)
     }
 
-    #[cfg(not(feature = "raw"))]
+    /// Consumes the remainder of a bundle whose command framing can't be read (for example a
+    /// command length that overruns the bundle), salvaging whatever header fields are present.
+    pub fn parse_malformed(input: Span) -> ParserResult<Command> {
+        map(
+            tuple((
+                peek(tuple((
+                    opt(le_u16),
+                    opt(CommandType::parse),
+                    opt(le_u8),
+                    opt(le_u32),
+                ))),
+                rest,
+            )),
+            |((length, action_type, player_id, index), bytes): (_, Span)| Command {
+                action_type: action_type.unwrap_or(CommandType::Unknown(0)),
+                player_id: player_id.map(|player_id| player_id & 0b0111_1111),
+                index: index.unwrap_or_default(),
+                data: CommandData::Malformed(format!(
+                    "invalid command length {} with {} bytes remaining in bundle",
+                    length.unwrap_or_default(),
+                    bytes.len()
+                )),
+                selection: Vec::new(),
+                bytes: bytes.to_vec(),
+            },
+        )(input)
+    }
+
     fn parse_type(action_type: CommandType) -> impl FnMut(Span) -> ParserResult<Command> {
         move |input: Span| {
-            map(
-                tuple((le_u8, le_u32, CommandData::parser_for_type(action_type))),
-                |(player_id, index, data)| Command {
-                    action_type,
-                    player_id: player_id & 0b0111_1111, // bit mask to turn eg 0x87 into 0x7
-                    index,
-                    data,
-                },
-            )(input)
+            match tuple((
+                le_u8,
+                le_u32,
+                |input| Self::peek_selection(input, action_type),
+                CommandData::parser_for_type(action_type),
+            ))(input)
+            {
+                Ok((remaining, (player_id, index, selection, data))) => Ok((
+                    remaining,
+                    Command {
+                        action_type,
+                        player_id: Some(player_id & 0b0111_1111), // bit mask to turn eg 0x87 into 0x7
+                        index,
+                        data,
+                        selection,
+                        bytes: input.fragment().to_vec(),
+                    },
+                )),
+                Err(err) => {
+                    let (_, (player_id, index)) = tuple((opt(le_u8), opt(le_u32)))(input)?;
+                    let (input, bytes) = rest(input)?;
+
+                    Ok((
+                        input,
+                        Command {
+                            action_type,
+                            player_id: player_id.map(|player_id| player_id & 0b0111_1111),
+                            index: index.unwrap_or_default(),
+                            data: CommandData::Malformed(Self::describe_error(err)),
+                            selection: Vec::new(),
+                            bytes: bytes.to_vec(),
+                        },
+                    ))
+                }
+            }
         }
     }
 
-    #[cfg(feature = "raw")]
-    fn parse_type(action_type: CommandType) -> impl FnMut(Span) -> ParserResult<Command> {
-        use nom::{combinator::eof, multi::many_till};
+    /// Selections that can't be decoded are treated as empty rather than failing the command.
+    fn peek_selection(input: Span, action_type: CommandType) -> ParserResult<Vec<u32>> {
+        if !action_type.is_unit_command() {
+            return Ok((input, Vec::new()));
+        }
 
-        move |input: Span| {
-            map(
-                tuple((
-                    peek(many_till(le_u8, eof)),
-                    le_u8,
-                    le_u32,
-                    CommandData::parser_for_type(action_type),
-                )),
-                |((bytes, _), player_id, index, data)| Command {
-                    action_type,
-                    player_id: player_id & 0b0111_1111, // bit mask to turn eg 0x87 into 0x7,
-                    index,
-                    data,
-                    bytes,
-                },
-            )(input)
+        Ok(peek(CommandData::parse_selection_prefix)(input).unwrap_or((input, Vec::new())))
+    }
+
+    fn describe_error(err: nom::Err<nom::error::Error<Span>>) -> String {
+        match err {
+            nom::Err::Incomplete(_) => "command data ended unexpectedly".to_string(),
+            nom::Err::Error(err) | nom::Err::Failure(err) => format!(
+                "failed to parse command data at byte {}: {}",
+                err.input.location_offset(),
+                err.code.description()
+            ),
         }
     }
 }
@@ -1,5 +1,5 @@
-use crate::data::parser::parse_utf16_variable;
-use crate::data::{ParserResult, Span};
+use crate::data::parser::{located, parse_utf16_variable};
+use crate::data::{Location, ParserResult, Span};
 use nom::combinator::{cut, map};
 use nom::number::complete::le_u32;
 use nom::sequence::tuple;
@@ -7,14 +7,33 @@ use nom::sequence::tuple;
 #[derive(Debug, Clone)]
 pub struct Message {
     pub name: String,
+    pub name_location: Location,
     pub message: String,
+    pub message_location: Location,
+    pub channel: u32,
+    pub sender_id: u64,
 }
 
 impl Message {
-    pub fn parse_message(input: Span) -> ParserResult<Message> {
-        cut(map(
-            tuple((parse_utf16_variable(le_u32), parse_utf16_variable(le_u32))),
-            |((_, name), (_, message))| Message { name, message },
-        ))(input)
+    pub fn parse_message(
+        channel: u32,
+        sender_id: u64,
+    ) -> impl FnMut(Span) -> ParserResult<Message> {
+        move |input: Span| {
+            cut(map(
+                tuple((
+                    located(parse_utf16_variable(le_u32)),
+                    located(parse_utf16_variable(le_u32)),
+                )),
+                |(((_, name), name_location), ((_, message), message_location))| Message {
+                    name,
+                    name_location,
+                    message,
+                    message_location,
+                    channel,
+                    sender_id,
+                },
+            ))(input)
+        }
     }
 }
@@ -1,30 +1,39 @@
+use crate::data::parser::located;
 use crate::data::ticks::{Message, Tick};
-use crate::data::{ParserResult, Span};
+use crate::data::{Location, ParserResult, Span};
 use nom::combinator::{cut, map, peek};
 use nom::multi::{length_data, length_value, many_m_n};
-use nom::number::complete::le_u32;
+use nom::number::complete::{le_u32, le_u64};
 use nom::sequence::tuple;
 
 #[derive(Debug)]
 pub struct MessageTick {
     _tick_type: u32,
+    pub location: Location,
+    pub body_location: Option<Location>,
+    pub sender_id_location: Option<Location>,
     pub messages: Vec<Message>,
 }
 
+type MessageContent = (Option<Location>, Option<Location>, Vec<Message>);
+
 impl MessageTick {
     pub fn parse_tick(input: Span) -> ParserResult<Tick> {
         map(
-            tuple((le_u32, length_value(le_u32, Self::parse_message))),
-            |(tick_type, messages)| {
+            tuple((le_u32, located(length_value(le_u32, Self::parse_message)))),
+            |(tick_type, ((body_location, sender_id_location, messages), location))| {
                 Tick::Message(MessageTick {
                     _tick_type: tick_type,
+                    location,
+                    body_location,
+                    sender_id_location,
                     messages,
                 })
             },
         )(input)
     }
 
-    fn parse_message(input: Span) -> ParserResult<Vec<Message>> {
+    fn parse_message(input: Span) -> ParserResult<MessageContent> {
         let (_, num_messages) = peek(le_u32)(input)?;
 
         if num_messages == 0 {
@@ -34,23 +43,29 @@ impl MessageTick {
         }
     }
 
-    fn parse_empty_message(input: Span) -> ParserResult<Vec<Message>> {
+    fn parse_empty_message(input: Span) -> ParserResult<MessageContent> {
         cut(map(tuple((le_u32, length_data(le_u32))), |(_, _)| {
-            Vec::new()
+            (None, None, Vec::new())
         }))(input)
     }
 
-    fn parse_content_message(input: Span, num_messages: u32) -> ParserResult<Vec<Message>> {
+    fn parse_content_message(input: Span, num_messages: u32) -> ParserResult<MessageContent> {
+        let (input, (_, body_length, channel, (sender_id, sender_id_location))) =
+            cut(tuple((le_u32, located(le_u32), le_u32, located(le_u64))))(input)?;
+
         cut(map(
-            tuple((
-                le_u32,
-                le_u32,
-                le_u32,
-                le_u32,
-                le_u32,
-                many_m_n(1, num_messages as usize, Message::parse_message),
-            )),
-            |(_, _, _, _, _, messages)| messages,
+            many_m_n(
+                1,
+                num_messages as usize,
+                Message::parse_message(channel, sender_id),
+            ),
+            move |messages| {
+                let body_location = Location {
+                    offset: body_length.1.offset,
+                    length: 4 + body_length.0 as usize,
+                };
+                (Some(body_location), Some(sender_id_location), messages)
+            },
         ))(input)
     }
 }
@@ -1,10 +1,118 @@
 //! Error representations, primarily related to parsing failure.
 
 use crate::data::Span;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
 
 /// Represents a failure in parsing at some point in the combinator chain. Parsing fails eagerly;
 /// that is, the first failure point hit will exit with an error. This type returns an error that
 /// includes information on the segment of bytes being parsed and the location of the cursor at
 /// time of failure.
-
 pub type ParseError<'a> = nom::Err<nom::error::Error<Span<'a>>>;
+
+/// An owned error returned when parsing a replay from a file or reader. Unlike `ParseError`, this
+/// type doesn't borrow from the replay's bytes, so it can outlive them and be sent across threads.
+/// When the replay was read from a file, the file's path is attached to the error.
+
+#[derive(Debug)]
+pub struct Error {
+    path: Option<PathBuf>,
+    version: Option<u16>,
+    kind: ErrorKind,
+}
+
+impl Error {
+    pub(crate) fn new(path: Option<&Path>, kind: ErrorKind) -> Self {
+        Self {
+            path: path.map(Path::to_path_buf),
+            version: None,
+            kind,
+        }
+    }
+
+    pub(crate) fn with_version(self, version: Option<u16>) -> Self {
+        Self { version, ..self }
+    }
+
+    pub(crate) fn from_parse_error(path: Option<&Path>, err: ParseError) -> Self {
+        let kind = match err {
+            nom::Err::Incomplete(_) => ErrorKind::Incomplete,
+            nom::Err::Error(err) | nom::Err::Failure(err) => ErrorKind::Parse {
+                offset: err.input.location_offset(),
+                code: err.code,
+            },
+        };
+
+        Self::new(path, kind)
+    }
+
+    /// Path of the file the replay was read from, or `None` if the replay was read from a reader
+    /// or byte slice.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+    /// The game version the replay was recorded on, if the replay's header could be read. See
+    /// `Replay::version` for more information.
+    pub fn version(&self) -> Option<u16> {
+        self.version
+    }
+    /// The underlying cause of the failure.
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{}: {}", path.display(), self.kind),
+            None => write!(f, "{}", self.kind),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            ErrorKind::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// The categories of failure that can occur while reading and parsing a replay.
+
+#[derive(Debug)]
+pub enum ErrorKind {
+    /// The replay's bytes couldn't be read.
+    Io(io::Error),
+    /// The replay's bytes didn't match the expected format. `offset` is the position of the cursor
+    /// in the replay's bytes at time of failure, and `code` identifies the combinator that failed.
+    Parse {
+        offset: usize,
+        code: nom::error::ErrorKind,
+    },
+    /// The replay ended before parsing could complete.
+    Incomplete,
+    /// The parser panicked on the replay's bytes. Holds the panic message, if it had one. Only
+    /// returned by batch parsing, which isolates each replay so one bad file can't abort the rest.
+    Panic(String),
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::Io(err) => write!(f, "failed to read replay: {}", err),
+            ErrorKind::Parse { offset, code } => write!(
+                f,
+                "failed to parse replay at offset {}: {}",
+                offset,
+                code.description()
+            ),
+            ErrorKind::Incomplete => write!(f, "replay ended unexpectedly"),
+            ErrorKind::Panic(message) => write!(f, "parser panicked: {}", message),
+        }
+    }
+}
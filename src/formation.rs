@@ -0,0 +1,99 @@
+//! Representation of reconstructed formation group information.
+
+use crate::command::Command;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Representation of a formation squad group, a set of squads that a player linked together so
+/// they move and fight as one. Replays don't record group membership directly, so groups are
+/// reconstructed by replaying the player's formation commands in order. To access, see
+/// `Player::formation_groups_at`.
+///
+/// No sample replay contains formation commands, so their layout is assumed to match other player
+/// and squad commands: squads are added to a group through a squad target, and squads join a group
+/// by selecting them and targeting a squad already in it. This hasn't been verified against a real
+/// replay.
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::FormationGroup"))]
+pub struct FormationGroup {
+    created_at: u32,
+    squads: Vec<u32>,
+}
+
+impl FormationGroup {
+    /// The tick at which the player finished creating the group. Because CoH3's engine runs at 8
+    /// ticks per second, you can divide this value by 8 to get the number of seconds since the
+    /// replay began.
+    pub fn created_at(&self) -> u32 {
+        self.created_at
+    }
+    /// Identifiers of the squads in the group, in the order they were added or joined. These use
+    /// the same numbering as `Selection::squads`.
+    pub fn squads(&self) -> &[u32] {
+        &self.squads
+    }
+}
+
+/// Replays the formation commands in `commands` up to and including `tick` and returns the groups
+/// that existed at that point, in the order they were created.
+pub(crate) fn formation_groups_at(commands: &[Command], tick: u32) -> Vec<FormationGroup> {
+    let mut groups: Vec<FormationGroup> = Vec::new();
+    let mut pending: Option<Vec<u32>> = None;
+
+    for command in commands {
+        match command {
+            Command::FormationGroupCreateBegin(begin) if begin.tick() <= tick => {
+                pending = Some(Vec::new());
+            }
+            Command::FormationGroupAddSquad(add) if add.tick() <= tick => {
+                if let (Some(squads), Some(squad)) = (
+                    pending.as_mut(),
+                    add.target().and_then(|target| target.squad()),
+                ) {
+                    if !squads.contains(&squad) {
+                        squads.push(squad);
+                    }
+                }
+            }
+            Command::FormationGroupCreateEnd(end) if end.tick() <= tick => {
+                if let Some(squads) = pending.take() {
+                    leave(&mut groups, &squads);
+                    if !squads.is_empty() {
+                        groups.push(FormationGroup {
+                            created_at: end.tick(),
+                            squads,
+                        });
+                    }
+                }
+            }
+            Command::JoinFormationGroup(join) if join.tick() <= tick => {
+                if let Some(member) = join.target().and_then(|target| target.squad()) {
+                    let joining = join.selection().squads();
+
+                    leave(&mut groups, joining);
+                    if let Some(group) = groups
+                        .iter_mut()
+                        .find(|group| group.squads.contains(&member))
+                    {
+                        group.squads.extend_from_slice(joining);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    groups
+}
+
+/// Removes the given squads from every group, since a squad can only be in one group at a time,
+/// and drops any group left empty.
+fn leave(groups: &mut Vec<FormationGroup>, squads: &[u32]) {
+    for group in groups.iter_mut() {
+        group.squads.retain(|squad| !squads.contains(squad));
+    }
+    groups.retain(|group| !group.squads.is_empty());
+}
@@ -0,0 +1,73 @@
+//! Representation of parsed item information.
+
+use crate::data::Item as ItemData;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Representation of an inventory item a player brought into a match, such as a commander
+/// portrait, unit skin or emblem. Items are parsed from the player's loadout and associated with
+/// the `Player` instance that owns them. To access, see `Player::items`.
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::Item"))]
+pub struct Item {
+    iteminstance_id: u64,
+    itemdefinition_id: u64,
+    profile_id: u64,
+    durability: u32,
+    durability_type: u32,
+    metadata: String,
+    itemlocation_id: u64,
+}
+
+impl Item {
+    /// Unique ID of this particular copy of the item in the player's inventory.
+    pub fn iteminstance_id(&self) -> u64 {
+        self.iteminstance_id
+    }
+    /// ID of the item definition, which identifies what the item is (i.e. which portrait, skin or
+    /// emblem). Every copy of the same item shares this ID.
+    pub fn itemdefinition_id(&self) -> u64 {
+        self.itemdefinition_id
+    }
+    /// The Relic profile ID of the player that owns the item. For details on what this ID
+    /// represents please see `Player::profile_id`.
+    pub fn profile_id(&self) -> u64 {
+        self.profile_id
+    }
+    /// Remaining durability of the item.
+    pub fn durability(&self) -> u32 {
+        self.durability
+    }
+    /// Integer representation of how the item's durability is consumed.
+    pub fn durability_type(&self) -> u32 {
+        self.durability_type
+    }
+    /// Free-form metadata attached to the item, usually a JSON string describing where the item is
+    /// equipped. The string is UTF-8 encoded.
+    pub fn metadata(&self) -> &str {
+        &self.metadata
+    }
+    /// ID of the inventory location (slot) the item is equipped in.
+    pub fn itemlocation_id(&self) -> u64 {
+        self.itemlocation_id
+    }
+}
+
+pub(crate) fn item_from_data(item_data: &ItemData) -> Item {
+    Item {
+        iteminstance_id: item_data.iteminstance_id,
+        itemdefinition_id: item_data.itemdefinition_id,
+        profile_id: item_data.profile_id,
+        durability: item_data.durability,
+        durability_type: item_data.durability_type,
+        metadata: item_data.metadata.clone(),
+        itemlocation_id: item_data.itemlocation_id,
+    }
+}
+
+// this is safe as Item does not contain any Ruby types
+#[cfg(feature = "magnus")]
+unsafe impl magnus::IntoValueFromNative for Item {}
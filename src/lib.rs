@@ -15,23 +15,35 @@
 //! }
 //! ```
 
+mod anonymize;
+pub mod batch;
 mod command;
 pub mod command_data;
 mod command_type;
 mod data;
 mod errors;
+mod formation;
+mod item;
 mod map;
 mod message;
 mod player;
 mod replay;
 
+pub use crate::anonymize::{anonymize, AnonymizeOptions};
 pub use crate::command::Command;
 #[cfg(feature = "raw")]
 pub use crate::command::RawCommand;
 pub use crate::command_type::CommandType;
+pub use crate::errors::Error;
+pub use crate::errors::ErrorKind;
 pub use crate::errors::ParseError;
+pub use crate::formation::FormationGroup;
+pub use crate::item::Item;
 pub use crate::map::Map;
 pub use crate::message::Message;
+pub use crate::message::MessageChannel;
+pub use crate::player::AiPersonality;
+pub use crate::player::AiType;
 pub use crate::player::Faction;
 pub use crate::player::Player;
 pub use crate::player::Team;
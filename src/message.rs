@@ -13,11 +13,22 @@ use serde::{Deserialize, Serialize};
 pub struct Message {
     tick: u32,
     message: String,
+    channel: MessageChannel,
+    sender_id: Option<u64>,
 }
 
 impl Message {
-    pub(crate) fn new(tick: u32, message: String) -> Self {
-        Self { tick, message }
+    pub(crate) fn new(tick: u32, message: String, channel: u32, sender_id: u64) -> Self {
+        Self {
+            tick,
+            message,
+            channel: MessageChannel::from(channel),
+            sender_id: if sender_id == 0 {
+                None
+            } else {
+                Some(sender_id)
+            },
+        }
     }
 
     /// This value is the tick at which the message was found while parsing the replay, which
@@ -31,8 +42,47 @@ impl Message {
     pub fn message(&self) -> &str {
         &self.message
     }
+    /// The audience the message was sent to, i.e. whether it was visible to every player or only
+    /// to the sender's team.
+    pub fn channel(&self) -> MessageChannel {
+        self.channel
+    }
+    /// Relic profile ID of the player that sent the message, as recorded alongside the message
+    /// itself. This matches `Player::profile_id` of the sending player and is `None` if the
+    /// replay didn't record a sender.
+    pub fn sender_id(&self) -> Option<u64> {
+        self.sender_id
+    }
 }
 
 // this is safe as Message does not contain any Ruby types
 #[cfg(feature = "magnus")]
 unsafe impl magnus::IntoValueFromNative for Message {}
+
+/// The audience of a chat message. The replay stores this as an integer value alongside each
+/// batch of messages. The value is relative to the player who recorded the replay: it's three
+/// times the sender's relation to that player (0 for the recording player, 1 for an ally, 2 for
+/// an enemy) plus 1 for all chat or 2 for team chat. Any other value is preserved as-is in
+/// `Unknown`.
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::MessageChannel"))]
+pub enum MessageChannel {
+    /// Messages visible to every player in the match.
+    All,
+    /// Messages visible only to the sender's team.
+    Team,
+    /// A channel value that hasn't been identified yet.
+    Unknown(u32),
+}
+
+impl From<u32> for MessageChannel {
+    fn from(input: u32) -> MessageChannel {
+        match input.checked_sub(1).map(|value| value % 3) {
+            Some(1) => MessageChannel::All,
+            Some(2) => MessageChannel::Team,
+            _ => MessageChannel::Unknown(input),
+        }
+    }
+}
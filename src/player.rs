@@ -1,7 +1,10 @@
 //! Representation of parsed player information.
 
 use crate::command::Command;
+use crate::command_data::{Cheat, SquadTarget};
 use crate::data::Player as PlayerData;
+use crate::formation::{formation_groups_at, FormationGroup};
+use crate::item::{item_from_data, Item};
 use crate::message::Message;
 use std::collections::HashMap;
 use std::convert::TryFrom;
@@ -28,6 +31,9 @@ pub struct Player {
     battlegroup: Option<u32>,
     steam_id: Option<u64>,
     profile_id: Option<u64>,
+    ai_personality: Option<AiPersonality>,
+    ai_type: Option<AiType>,
+    items: Vec<Item>,
     messages: Vec<Message>,
     commands: Vec<Command>,
     #[cfg(feature = "raw")]
@@ -71,6 +77,25 @@ impl Player {
     pub fn profile_id(&self) -> Option<u64> {
         self.profile_id
     }
+    /// The AI personality controlling the player, or `None` if the player is human. Every AI
+    /// player in the bundled replays uses the default personality.
+    pub fn ai_personality(&self) -> Option<AiPersonality> {
+        self.ai_personality.clone()
+    }
+    /// The difficulty of the AI controlling the player, or `None` if the player is human.
+    ///
+    /// Replays don't store the difficulty anywhere that has been identified, so it's read from the
+    /// player's name, which the game sets to e.g. "CPU - Standard". Only the English names of the
+    /// built-in difficulties are recognized, and only "CPU - Standard" has been seen in a sample
+    /// replay. A renamed or localized AI player is returned as `AiType::Other` holding its name.
+    pub fn ai_type(&self) -> Option<AiType> {
+        self.ai_type.clone()
+    }
+    /// A list of the inventory items the player brought into the match, such as commander
+    /// portraits, unit skins and emblems. Always empty for AI players.
+    pub fn items(&self) -> Vec<Item> {
+        self.items.clone()
+    }
     /// A list of all messages sent by the player in the match. Sorted chronologically from first
     /// to last.
     pub fn messages(&self) -> Vec<Message> {
@@ -91,8 +116,8 @@ impl Player {
     }
 
     /// A list of only build-related commands executed by the player in the match. A build command
-    /// is any that enqueues the construction of a new unit or upgrade. Sorted chronologically from
-    /// first to last.
+    /// is any that enqueues the construction of a new unit or upgrade, including upgrades applied
+    /// to individual squads. Sorted chronologically from first to last.
     pub fn build_commands(&self) -> Vec<Command> {
         self.commands
             .clone()
@@ -100,12 +125,70 @@ impl Player {
             .filter(|entry| {
                 matches!(
                     entry,
-                    Command::BuildGlobalUpgrade(_) | Command::BuildSquad(_)
+                    Command::BuildGlobalUpgrade(_)
+                        | Command::BuildSquad(_)
+                        | Command::SquadUpgrade(_)
                 )
             })
             .collect()
     }
 
+    /// The number of times the player reinforced a squad in the match.
+    pub fn reinforcement_count(&self) -> usize {
+        self.commands
+            .iter()
+            .filter(|entry| matches!(entry, Command::Reinforce(_)))
+            .count()
+    }
+
+    /// All capture commands issued by the player in the match, grouped by the identifier of the
+    /// strategic point entity they targeted. Each group is sorted chronologically from first to
+    /// last, so comparing the first tick of each group shows the order in which the player
+    /// prioritized territory.
+    pub fn captures_by_target(&self) -> HashMap<u32, Vec<SquadTarget>> {
+        let mut captures: HashMap<u32, Vec<SquadTarget>> = HashMap::new();
+
+        for command in &self.commands {
+            if let Command::Capture(capture) = command {
+                if let Some(target) = capture.target().entity() {
+                    captures.entry(target).or_default().push(capture.clone());
+                }
+            }
+        }
+
+        captures
+    }
+
+    /// The formation squad groups the player had at the given tick, reconstructed from the
+    /// player's formation commands up to and including that tick. This is a best-effort
+    /// reconstruction: squads that die or leave a group without a command aren't removed from it.
+    pub fn formation_groups_at(&self, tick: u32) -> Vec<FormationGroup> {
+        formation_groups_at(&self.commands, tick)
+    }
+
+    /// All cheats the player activated in the match. Sorted chronologically from first to last.
+    /// Cheat commands that couldn't be decoded aren't included here; see `Player::cheated`.
+    pub fn cheats(&self) -> Vec<Cheat> {
+        self.commands
+            .iter()
+            .filter_map(|entry| match entry {
+                Command::Cheat(cheat) => Some(cheat.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Whether the player issued any cheat command in the match. Unlike `Player::cheats`, this
+    /// checks the type of every command, so cheats that couldn't be decoded are still counted.
+    pub fn cheated(&self) -> bool {
+        self.commands.iter().any(|entry| match entry {
+            Command::Cheat(_) => true,
+            Command::Unknown(unknown) => unknown.action_type().is_cheat(),
+            Command::Malformed(malformed) => malformed.action_type().is_cheat(),
+            _ => false,
+        })
+    }
+
     /// A list of only battlegroup-related commands executed by the player in the match. A
     /// battlegroup command is any that involves the select or use of battlegroups and their
     /// abilities.
@@ -127,8 +210,8 @@ impl Player {
 
 pub(crate) fn player_from_data(
     player_data: &PlayerData,
-    messages: &HashMap<String, Vec<Message>>,
-    commands: &HashMap<u32, Vec<Command>>,
+    messages: &HashMap<u64, Vec<Message>>,
+    commands: &HashMap<Option<u32>, Vec<Command>>,
     #[cfg(feature = "raw")] raw_commands: &HashMap<u32, Vec<RawCommand>>,
 ) -> Player {
     let mut player = Player {
@@ -138,8 +221,26 @@ pub(crate) fn player_from_data(
         team: Team::try_from(player_data.team).unwrap(),
         steam_id: None,
         profile_id: None,
-        messages: messages.get(&player_data.name).cloned().unwrap_or_default(),
-        commands: commands.get(&player_data.id).cloned().unwrap_or_default(),
+        ai_personality: None,
+        ai_type: None,
+        items: player_data
+            .items
+            .iter()
+            .filter_map(|record| record.item.as_ref())
+            .map(item_from_data)
+            .collect(),
+        messages: if player_data.human != 0 {
+            messages
+                .get(&player_data.profile_id)
+                .cloned()
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        },
+        commands: commands
+            .get(&Some(player_data.id))
+            .cloned()
+            .unwrap_or_default(),
         #[cfg(feature = "raw")]
         raw_commands: raw_commands
             .get(&player_data.id)
@@ -151,6 +252,9 @@ pub(crate) fn player_from_data(
     if player.human {
         player.steam_id = Some(str::parse(&player_data.steam_id).unwrap());
         player.profile_id = Some(player_data.profile_id);
+    } else {
+        player.ai_personality = Some(AiPersonality::from(player_data.ai_type.as_ref()));
+        player.ai_type = Some(AiType::from(player.name.as_ref()));
     }
 
     player.battlegroup = match player
@@ -241,3 +345,72 @@ impl TryFrom<u32> for Team {
         }
     }
 }
+
+/// AI personalities that can control non-human players. Personalities that aren't recognized are
+/// preserved as their raw identifier in `Other`.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::AiPersonality"))]
+pub enum AiPersonality {
+    Default,
+    Other(String),
+}
+
+impl Display for AiPersonality {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            AiPersonality::Default => write!(f, "default_ai_personality"),
+            AiPersonality::Other(personality) => write!(f, "{}", personality),
+        }
+    }
+}
+
+impl From<&str> for AiPersonality {
+    fn from(input: &str) -> AiPersonality {
+        match input {
+            "default_ai_personality" => AiPersonality::Default,
+            _ => AiPersonality::Other(input.to_string()),
+        }
+    }
+}
+
+/// Difficulty levels of AI players, as derived from their names by `Player::ai_type`. Names that
+/// don't match a built-in difficulty are preserved in `Other`, without the "CPU - " prefix.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::AiType"))]
+pub enum AiType {
+    Easy,
+    Standard,
+    Hard,
+    Expert,
+    Other(String),
+}
+
+impl Display for AiType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            AiType::Easy => write!(f, "Easy"),
+            AiType::Standard => write!(f, "Standard"),
+            AiType::Hard => write!(f, "Hard"),
+            AiType::Expert => write!(f, "Expert"),
+            AiType::Other(difficulty) => write!(f, "{}", difficulty),
+        }
+    }
+}
+
+impl From<&str> for AiType {
+    fn from(input: &str) -> AiType {
+        let difficulty = input.strip_prefix("CPU - ").unwrap_or(input);
+
+        match difficulty {
+            "Easy" => AiType::Easy,
+            "Standard" => AiType::Standard,
+            "Hard" => AiType::Hard,
+            "Expert" => AiType::Expert,
+            _ => AiType::Other(difficulty.to_string()),
+        }
+    }
+}
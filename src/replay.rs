@@ -1,7 +1,9 @@
 //! Representation of parsed replay information.
 
+use crate::command::Command;
 use crate::data::chunks::DataAutoChunk;
 use crate::data::{Replay as ReplayData, Span};
+use crate::errors::{Error, ErrorKind};
 use crate::map::{map_from_data, Map};
 use crate::player::{player_from_data, Player};
 use crate::ParseError;
@@ -9,6 +11,9 @@ use nom_locate::LocatedSpan;
 use nom_tracable::TracableInfo;
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
 use uuid::Uuid;
 
 #[cfg(feature = "serde")]
@@ -30,6 +35,8 @@ pub struct Replay {
     map: Map,
     players: Vec<Player>,
     length: usize,
+    truncated_at: Option<usize>,
+    unattributed_commands: Vec<Command>,
 }
 
 impl Replay {
@@ -43,13 +50,101 @@ impl Replay {
     ///     assert!(replay.is_ok())
     /// }
     /// ```
-    pub fn from_bytes(input: &[u8]) -> Result<Replay, ParseError> {
+    pub fn from_bytes(input: &[u8]) -> Result<Replay, ParseError<'_>> {
         let info = TracableInfo::new().parser_width(64).fold("term");
         let input: Span = LocatedSpan::new_extra(input, info);
         let (_, replay) = ReplayData::from_span(input)?;
         Ok(replay_from_data(&replay))
     }
 
+    /// Takes a byte slice and parses it as a CoH3 replay like `Replay::from_bytes`, but tolerates
+    /// replays that end partway through a tick, such as those recorded by games that crashed or
+    /// were force-closed. Instead of failing, parsing stops at the first tick that can't be read
+    /// and the replay is returned with every tick before it, with `Replay::truncated_at` set to the
+    /// byte offset of the unreadable tick. Failures outside of the ticks, such as a corrupt header
+    /// or player list, still return an error.
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     let data = include_bytes!("/path/to/crashed.rec");
+    ///     let replay = vault::Replay::from_bytes_lenient(data).unwrap();
+    ///     assert!(replay.truncated_at().is_some())
+    /// }
+    /// ```
+    pub fn from_bytes_lenient(input: &[u8]) -> Result<Replay, ParseError<'_>> {
+        let info = TracableInfo::new().parser_width(64).fold("term");
+        let input: Span = LocatedSpan::new_extra(input, info);
+        let (_, replay) = ReplayData::from_span_lenient(input)?;
+        Ok(replay_from_data(&replay))
+    }
+
+    /// Reads the file at the given path, parses it as a CoH3 replay, and returns a representation
+    /// of the parsed information. Any failures while reading the file or during parsing or
+    /// conversion will return an error with the path attached.
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     let replay = vault::Replay::from_path("/path/to/replay.rec");
+    ///     assert!(replay.is_ok())
+    /// }
+    /// ```
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Replay, Error> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|err| Error::new(Some(path), ErrorKind::Io(err)))?;
+        Self::from_reader_at(file, Some(path))
+    }
+
+    /// Reads all bytes from the given reader, parses them as a CoH3 replay, and returns a
+    /// representation of the parsed information. Any failures while reading or during parsing or
+    /// conversion will return an error.
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     let file = std::fs::File::open("/path/to/replay.rec").unwrap();
+    ///     let replay = vault::Replay::from_reader(file);
+    ///     assert!(replay.is_ok())
+    /// }
+    /// ```
+    pub fn from_reader<R: Read>(reader: R) -> Result<Replay, Error> {
+        Self::from_reader_at(reader, None)
+    }
+
+    /// Memory maps the file at the given path, parses it as a CoH3 replay, and returns a
+    /// representation of the parsed information. This avoids copying the file into memory, which
+    /// can be faster when parsing large batches of replays. Any failures while mapping the file or
+    /// during parsing or conversion will return an error with the path attached.
+    ///
+    /// The file must not be modified or truncated while it's being parsed.
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     let replay = vault::Replay::from_path_mmap("/path/to/replay.rec");
+    ///     assert!(replay.is_ok())
+    /// }
+    /// ```
+    #[cfg(feature = "mmap")]
+    pub fn from_path_mmap<P: AsRef<Path>>(path: P) -> Result<Replay, Error> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|err| Error::new(Some(path), ErrorKind::Io(err)))?;
+        // SAFETY: the mapping is only read for the duration of parsing, and the parsed replay
+        // doesn't borrow from it. Concurrent modification of the file is documented as unsupported.
+        let mmap = unsafe { memmap2::Mmap::map(&file) }
+            .map_err(|err| Error::new(Some(path), ErrorKind::Io(err)))?;
+        Self::from_bytes(&mmap).map_err(|err| {
+            Error::from_parse_error(Some(path), err).with_version(version_from_bytes(&mmap))
+        })
+    }
+
+    fn from_reader_at<R: Read>(mut reader: R, path: Option<&Path>) -> Result<Replay, Error> {
+        let mut buffer = Vec::new();
+        reader
+            .read_to_end(&mut buffer)
+            .map_err(|err| Error::new(path, ErrorKind::Io(err)))?;
+        Self::from_bytes(&buffer).map_err(|err| {
+            Error::from_parse_error(path, err).with_version(version_from_bytes(&buffer))
+        })
+    }
+
     /// The Company of Heroes 3 game version this replay was recorded on. Note that this is probably
     /// more accurated described as the build version, and represents the final segment of digits
     /// you see in the game version on the game's main menu. Every time the game is patched, this
@@ -113,6 +208,24 @@ impl Replay {
     pub fn length(&self) -> usize {
         self.length
     }
+    /// The byte offset at which the replay was found to be cut off, or `None` if the replay was
+    /// complete. Only replays parsed with `Replay::from_bytes_lenient` can be truncated; for those,
+    /// all information parsed from ticks (commands, messages and length) only covers the match up
+    /// to this point.
+    pub fn truncated_at(&self) -> Option<usize> {
+        self.truncated_at
+    }
+    /// Malformed commands whose player ID couldn't be read, so they can't be associated with any
+    /// player. Sorted chronologically from first to last. These are always `Command::Malformed`.
+    pub fn unattributed_commands(&self) -> Vec<Command> {
+        self.unattributed_commands.clone()
+    }
+    /// Whether any player issued a cheat command during the match, including cheats that couldn't
+    /// be decoded. Replays where this is `true` don't reflect a fair game; see `Player::cheats` for
+    /// which cheats were used and when.
+    pub fn cheats_used(&self) -> bool {
+        self.players.iter().any(Player::cheated)
+    }
 }
 
 fn replay_from_data(data: &ReplayData) -> Replay {
@@ -129,6 +242,8 @@ fn replay_from_data(data: &ReplayData) -> Replay {
         mod_uuid: data.game_data().mod_uuid,
         map: map_from_data(data.map_data()),
         length: data.command_ticks().count(),
+        truncated_at: data.truncated_at,
+        unattributed_commands: commands.get(&None).cloned().unwrap_or_default(),
         players: data
             .game_data()
             .players
@@ -146,6 +261,14 @@ fn replay_from_data(data: &ReplayData) -> Replay {
     }
 }
 
+fn version_from_bytes(input: &[u8]) -> Option<u16> {
+    let info = TracableInfo::new().parser_width(64).fold("term");
+    let input: Span = LocatedSpan::new_extra(input, info);
+    ReplayData::parse_version(input)
+        .ok()
+        .map(|(_, version)| version)
+}
+
 fn matchhistory_id_from_data(data: &ReplayData) -> Option<u64> {
     if game_type_from_data(data) == GameType::Skirmish {
         None
@@ -2,14 +2,14 @@
 
 extern crate vault;
 
-use std::{
-    collections::HashSet,
-    fs::{self, File},
-    io::Read,
-    thread,
-};
+use std::{collections::HashSet, path::Path};
 use uuid::{uuid, Uuid};
-use vault::{Command, CommandType, GameType, Replay};
+use vault::batch::{self, BatchOptions, FailureKind};
+use vault::command_data::{CheatKind, Target};
+use vault::{
+    anonymize, AiPersonality, AiType, AnonymizeOptions, Command, CommandType, ErrorKind, GameType,
+    MessageChannel, Replay,
+};
 
 #[test]
 fn parse_success() {
@@ -53,6 +53,22 @@ fn parse_success_ai() {
             .collect::<Vec<&str>>(),
         vec!["Janne252", "CPU - Standard"]
     );
+    assert_eq!(
+        unwrapped
+            .players()
+            .iter()
+            .map(|player| { player.ai_personality() })
+            .collect::<Vec<Option<AiPersonality>>>(),
+        vec![None, Some(AiPersonality::Default)]
+    );
+    assert_eq!(
+        unwrapped
+            .players()
+            .iter()
+            .map(|player| { player.ai_type() })
+            .collect::<Vec<Option<AiType>>>(),
+        vec![None, Some(AiType::Standard)]
+    );
     assert_eq!(
         unwrapped.mod_uuid(),
         uuid!("385d9810-96ba-4ece-9040-8281db65174e")
@@ -61,6 +77,892 @@ fn parse_success_ai() {
     assert_eq!(unwrapped.matchhistory_id(), None);
 }
 
+#[test]
+fn parse_from_path() {
+    let replay = Replay::from_path("replays/USvDAK_v10612.rec").unwrap();
+    assert_eq!(replay.version(), 10612);
+
+    let err = Replay::from_path("replays/missing.rec").unwrap_err();
+    assert_eq!(err.path(), Some(Path::new("replays/missing.rec")));
+    assert!(matches!(err.kind(), ErrorKind::Io(_)));
+
+    let err = Replay::from_path("CoH3Rec.bt").unwrap_err();
+    assert_eq!(err.path(), Some(Path::new("CoH3Rec.bt")));
+    assert!(matches!(err.kind(), ErrorKind::Parse { .. }));
+}
+
+#[test]
+fn parse_from_reader() {
+    let data: &[u8] = include_bytes!("../replays/USvDAK_v10612.rec");
+    let replay = Replay::from_reader(data).unwrap();
+    assert_eq!(replay.version(), 10612);
+
+    let err = Replay::from_reader(&[1u8, 2, 3][..]).unwrap_err();
+    assert_eq!(err.path(), None);
+    assert_eq!(err.version(), None);
+
+    let err = Replay::from_reader(&data[..1000]).unwrap_err();
+    assert_eq!(err.version(), Some(10612));
+}
+
+#[test]
+#[cfg(feature = "mmap")]
+fn parse_from_path_mmap() {
+    let replay = Replay::from_path_mmap("replays/USvDAK_v10612.rec").unwrap();
+    assert_eq!(replay.version(), 10612);
+
+    let err = Replay::from_path_mmap("replays/missing.rec").unwrap_err();
+    assert_eq!(err.path(), Some(Path::new("replays/missing.rec")));
+}
+
+#[test]
+fn parse_truncated() {
+    let data = include_bytes!("../replays/USvDAK_v10612.rec");
+    let truncated = &data[..data.len() / 2];
+    assert!(Replay::from_bytes(truncated).is_err());
+
+    let replay = Replay::from_bytes_lenient(truncated).unwrap();
+    let original = Replay::from_bytes(data).unwrap();
+    let offset = replay.truncated_at().unwrap();
+    assert!(offset <= truncated.len());
+    assert!(replay.length() > 0 && replay.length() < original.length());
+    assert_eq!(replay.map_filename(), original.map_filename());
+    assert_eq!(
+        replay
+            .players()
+            .iter()
+            .map(|player| { player.name() })
+            .collect::<Vec<&str>>(),
+        vec!["madhax", "Quixalotl"]
+    );
+    assert_eq!(replay.players()[0].messages()[0].message(), "glhf");
+
+    let replay = Replay::from_bytes_lenient(data).unwrap();
+    assert_eq!(replay.truncated_at(), None);
+    assert_eq!(replay.length(), original.length());
+}
+
+#[test]
+fn parse_malformed_command() {
+    let data = include_bytes!("../replays/USvDAK_v10612.rec");
+    let original = Replay::from_bytes(data).unwrap();
+
+    // shorten the player's first CMD_BuildSquad so its data can't be parsed
+    let offset = command_offset(data, CommandType::CMD_BuildSquad, 0, 1);
+    let mut corrupted = data.to_vec();
+    corrupted[offset - 2..offset].copy_from_slice(&10u16.to_le_bytes());
+    let replay = Replay::from_bytes(&corrupted).unwrap();
+    let malformed: Vec<_> = replay.players()[0]
+        .commands()
+        .into_iter()
+        .filter_map(|command| match command {
+            Command::Malformed(data) => Some(data),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(malformed[0].tick(), 28);
+    assert_eq!(malformed[0].index(), 1);
+    assert_eq!(malformed[0].action_type(), CommandType::CMD_BuildSquad);
+    assert_eq!(malformed[0].bytes(), &data[offset + 1..offset + 8]);
+    assert!(!malformed[0].reason().is_empty());
+    assert_eq!(
+        replay.players()[1].commands().len(),
+        original.players()[1].commands().len()
+    );
+
+    // declare a length longer than the rest of the bundle
+    corrupted[offset - 2..offset].copy_from_slice(&u16::MAX.to_le_bytes());
+    let replay = Replay::from_bytes(&corrupted).unwrap();
+    assert!(replay.players()[0]
+        .commands()
+        .iter()
+        .any(|command| matches!(command, Command::Malformed(_))));
+    assert_eq!(replay.length(), original.length());
+
+    // end the command right after its type, before the player ID
+    corrupted[offset - 2..offset].copy_from_slice(&3u16.to_le_bytes());
+    let replay = Replay::from_bytes(&corrupted).unwrap();
+    let unattributed = replay.unattributed_commands();
+
+    assert!(original.unattributed_commands().is_empty());
+    assert!(matches!(
+        &unattributed[0],
+        Command::Malformed(data)
+            if data.tick() == 28 && data.action_type() == CommandType::CMD_BuildSquad
+    ));
+}
+
+#[test]
+fn parse_selection() {
+    let data = include_bytes!("../replays/one_seven_zero.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    let commands = replay.players()[0].commands();
+    let retreat = commands
+        .iter()
+        .find_map(|command| match command {
+            Command::Unknown(data)
+                if data.action_type() == CommandType::SCMD_Retreat && data.tick() == 2224 =>
+            {
+                Some(data)
+            }
+            _ => None,
+        })
+        .unwrap();
+
+    assert_eq!(retreat.selection().squads(), &[0x28, 0x20]);
+    assert!(retreat.selection().entities().is_empty());
+    assert_eq!(retreat.selection().len(), 2);
+
+    let build = commands
+        .iter()
+        .find_map(|command| match command {
+            Command::BuildSquad(data) => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(build.selection().entities(), &[0xde1]);
+}
+
+#[test]
+fn parse_reinforce() {
+    let data = include_bytes!("../replays/one_seven_zero.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    let player = &replay.players()[1];
+    let reinforcements: Vec<_> = player
+        .commands()
+        .into_iter()
+        .filter_map(|command| match command {
+            Command::Reinforce(data) => Some(data),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(player.reinforcement_count(), 5);
+    assert_eq!(reinforcements[0].tick(), 2333);
+    assert_eq!(reinforcements[0].squad_identifier(), Some(0x23));
+    assert_eq!(reinforcements[0].pbgid(), 199268);
+    assert_eq!(
+        reinforcements
+            .iter()
+            .map(|data| data.squad_identifier().unwrap())
+            .collect::<Vec<u32>>(),
+        vec![0x23, 0x17, 0x17, 0x40, 0x40]
+    );
+}
+
+#[test]
+fn parse_captures() {
+    let data = include_bytes!("../replays/one_seven_zero.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    let player = &replay.players()[1];
+    let captures: Vec<_> = player
+        .commands()
+        .into_iter()
+        .filter_map(|command| match command {
+            Command::Capture(data) => Some(data),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(captures.len(), 39);
+    assert_eq!(captures[0].tick(), 49);
+    assert_eq!(captures[0].target(), Target::Entity(1000007970));
+    assert_eq!(captures[0].selection().squads(), &[5]);
+
+    let by_target = player.captures_by_target();
+    assert_eq!(by_target.len(), 6);
+    assert_eq!(by_target[&1000007970].len(), 3);
+    assert_eq!(by_target.values().map(Vec::len).sum::<usize>(), 39);
+}
+
+#[test]
+fn parse_placements() {
+    let data = include_bytes!("../replays/one_seven_zero.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    let placements: Vec<_> = replay.players()[3]
+        .commands()
+        .into_iter()
+        .filter_map(|command| match command {
+            Command::ConstructEntity(data) => Some(data),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(placements.len(), 68);
+    assert_eq!(placements.iter().filter(|data| data.is_drag()).count(), 17);
+    assert_eq!(placements[0].tick(), 444);
+    assert_eq!(placements[0].pbgid(), 170271);
+    assert!((placements[0].start().x() - 84.3735).abs() < 0.001);
+    assert!((placements[0].end().z() + 62.1123).abs() < 0.001);
+    assert!((placements[0].rotation() - 1.1959).abs() < 0.001);
+    assert_eq!(placements[0].builders(), &[50001]);
+
+    let build = replay.players()[1]
+        .commands()
+        .into_iter()
+        .find_map(|command| match command {
+            Command::BuildStructure(data) => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(build.tick(), 17039);
+    assert_eq!(build.target(), Target::Entity(1000017032));
+    assert_eq!(build.selection().squads(), &[0xbf, 0x23, 0x17, 0x40]);
+}
+
+#[test]
+fn parse_team_weapons() {
+    let data = include_bytes!("../replays/one_seven_zero.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    let pickups: Vec<_> = replay.players()[0]
+        .commands()
+        .into_iter()
+        .filter_map(|command| match command {
+            Command::CaptureTeamWeapon(data) => Some(data),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(pickups[0].tick(), 11851);
+    assert_eq!(pickups[0].target(), Target::Entity(1000013517));
+    assert_eq!(pickups[0].selection().squads(), &[0x1a]);
+    assert_eq!(
+        pickups
+            .iter()
+            .find(|data| data.tick() == 20576)
+            .unwrap()
+            .selection()
+            .squads(),
+        &[0x135, 0x58]
+    );
+}
+
+#[test]
+fn parse_transports() {
+    let data = include_bytes!("../replays/one_seven_zero.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    let commands = replay.players()[3].commands();
+
+    let load = commands
+        .iter()
+        .find_map(|command| match command {
+            Command::Load(data) => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(load.tick(), 1649);
+    assert_eq!(load.target(), Target::Squad(0xc37c));
+    assert_eq!(load.selection().squads(), &[0x1d]);
+
+    // the transport is one of the player's own squads, selected under a lower number
+    let transport = load.target().squad().unwrap();
+    assert_eq!(transport, 44);
+    assert!(commands.iter().any(|command| matches!(
+        command,
+        Command::Unknown(data) if data.action_type() == CommandType::SCMD_Move
+            && data.selection().squads().contains(&transport)
+    )));
+
+    let unload = commands
+        .iter()
+        .find_map(|command| match command {
+            Command::Unload(data) => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(unload.tick(), 2128);
+    assert_eq!(unload.selection().squads(), &[0x1d]);
+    match unload.target() {
+        Some(Target::Position(position)) => assert!((position.x() - 163.6487).abs() < 0.001),
+        target => panic!("unexpected unload target {:?}", target),
+    }
+}
+
+#[test]
+fn parse_squad_state() {
+    // no samples contain these commands, so this only checks that each command type is routed to
+    // its variant with the selection read; the state values themselves aren't decoded
+    let data = include_bytes!("../replays/one_seven_zero.rec");
+    let patched = retype(
+        data,
+        &[
+            (
+                CommandType::SCMD_Stop,
+                1,
+                110,
+                CommandType::SCMD_CombatStance,
+            ),
+            (
+                CommandType::SCMD_Stop,
+                1,
+                111,
+                CommandType::SCMD_WeaponPreference,
+            ),
+            (
+                CommandType::SCMD_Stop,
+                1,
+                626,
+                CommandType::SCMD_SetMoveType,
+            ),
+            (
+                CommandType::SCMD_Stop,
+                1,
+                642,
+                CommandType::SCMD_HoldPosition,
+            ),
+            (CommandType::SCMD_Stop, 1, 643, CommandType::SCMD_Surprise),
+            (
+                CommandType::CMD_CancelProduction,
+                3,
+                751,
+                CommandType::CMD_HoldPositionOn,
+            ),
+            (
+                CommandType::CMD_CancelProduction,
+                3,
+                4337,
+                CommandType::CMD_HoldPositionOff,
+            ),
+        ],
+    );
+    let replay = Replay::from_bytes(&patched).unwrap();
+    let commands: Vec<_> = replay
+        .players()
+        .iter()
+        .flat_map(|player| player.commands())
+        .collect();
+
+    let states: Vec<_> = commands
+        .iter()
+        .filter_map(|command| match command {
+            Command::SetCombatStance(data)
+            | Command::SetWeaponPreference(data)
+            | Command::SetMoveType(data)
+            | Command::HoldPosition(data)
+            | Command::Surprise(data)
+            | Command::HoldPositionOn(data)
+            | Command::HoldPositionOff(data) => Some(data),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(states.len(), 7);
+    assert!(commands.iter().any(|command| matches!(
+        command,
+        Command::SetCombatStance(data) if data.index() == 110 && data.selection().squads() == [0x2c]
+    )));
+    assert!(commands.iter().any(|command| matches!(
+        command,
+        Command::HoldPositionOn(data) if data.index() == 751 && !data.selection().entities().is_empty()
+    )));
+}
+
+#[test]
+fn parse_stops() {
+    let data = include_bytes!("../replays/one_seven_zero.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    let commands = replay.players()[3].commands();
+
+    let stop = commands
+        .iter()
+        .find_map(|command| match command {
+            Command::Stop(data) => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(stop.tick(), 1479);
+    assert_eq!(stop.target(), None);
+    assert_eq!(stop.selection().squads(), &[0x2c]);
+
+    let stop_ability = commands
+        .iter()
+        .find_map(|command| match command {
+            Command::StopAbility(data) if data.tick() == 3585 => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(stop_ability.pbgid(), 2163470);
+    assert_eq!(stop_ability.selection().entities(), &[0x10e8]);
+}
+
+#[test]
+fn parse_abilities() {
+    let data = include_bytes!("../replays/one_seven_zero.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    let commands = replay.players()[0].commands();
+
+    let entity_ability = commands
+        .iter()
+        .find_map(|command| match command {
+            Command::UseAbility(data) => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(entity_ability.tick(), 28);
+    assert_eq!(entity_ability.pbgid(), 174094);
+    assert_eq!(entity_ability.source_identifier(), 13);
+    assert_eq!(entity_ability.selection().entities(), &[3328]);
+    assert!(matches!(
+        entity_ability.target(),
+        Some(Target::Position(position)) if position.x() == -152.5 && position.z() == -132.5
+    ));
+
+    let squad_abilities: Vec<_> = commands
+        .iter()
+        .filter_map(|command| match command {
+            Command::UseSquadAbility(data) => Some(data),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(squad_abilities[0].tick(), 3820);
+    assert_eq!(squad_abilities[0].pbgid(), Some(171201));
+    assert_eq!(
+        squad_abilities[0].target(),
+        Some(Target::Entity(1000009089))
+    );
+    assert_eq!(squad_abilities[0].selection().squads(), &[26]);
+
+    let context_ability = squad_abilities
+        .iter()
+        .find(|data| data.tick() == 7769)
+        .unwrap();
+    assert_eq!(context_ability.pbgid(), None);
+    assert_eq!(context_ability.target(), Some(Target::Entity(1000011064)));
+
+    let battlegroup_ability = commands
+        .iter()
+        .find_map(|command| match command {
+            Command::UseBattlegroupAbility(data) => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(battlegroup_ability.tick(), 4595);
+    assert_eq!(battlegroup_ability.pbgid(), 197104);
+    assert!(matches!(
+        battlegroup_ability.target(),
+        Some(Target::Position(_))
+    ));
+}
+
+#[test]
+fn parse_squad_upgrades() {
+    let data = include_bytes!("../replays/one_seven_zero.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    let player = &replay.players()[0];
+    let upgrades: Vec<_> = player
+        .commands()
+        .into_iter()
+        .filter_map(|command| match command {
+            Command::SquadUpgrade(data) => Some(data),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(upgrades[0].tick(), 40);
+    assert_eq!(upgrades[0].pbgid(), 170751);
+    assert_eq!(upgrades[0].squad_identifier(), Some(0));
+    assert_eq!(
+        player
+            .build_commands()
+            .iter()
+            .filter(|command| matches!(command, Command::SquadUpgrade(_)))
+            .count(),
+        upgrades.len()
+    );
+}
+
+#[test]
+fn parse_rally_points() {
+    let data = include_bytes!("../replays/one_seven_zero.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    let rally_points: Vec<_> = replay.players()[0]
+        .commands()
+        .into_iter()
+        .filter_map(|command| match command {
+            Command::RallyPoint(data) => Some(data),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(rally_points[0].tick(), 280);
+    assert_eq!(rally_points[0].source_identifier(), Some(0xde1));
+    assert!(matches!(
+        rally_points[0].target(),
+        Target::Position(position) if (position.x() - 15.8298).abs() < 0.001
+    ));
+
+    let data = include_bytes!("../replays/USvDAK_v10612.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    let rally_point = replay.players()[0]
+        .commands()
+        .into_iter()
+        .find_map(|command| match command {
+            Command::RallyPoint(data) => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(rally_point.tick(), 437);
+    assert!(matches!(rally_point.target(), Target::Entity(_)));
+}
+
+#[test]
+fn parse_demolitions() {
+    // no samples contain these commands, so this only checks that each command type is routed to
+    // its variant with the selection and target laid out like other squad and player commands
+    let data = include_bytes!("../replays/one_seven_zero.rec");
+    let patched = retype(
+        data,
+        &[
+            (
+                CommandType::SCMD_Capture,
+                0,
+                5,
+                CommandType::SCMD_PlaceCharge,
+            ),
+            (
+                CommandType::SCMD_Capture,
+                0,
+                14,
+                CommandType::SCMD_DefuseCharge,
+            ),
+            (
+                CommandType::SCMD_Capture,
+                0,
+                25,
+                CommandType::SCMD_DefuseMine,
+            ),
+            (
+                CommandType::CMD_CancelProduction,
+                0,
+                300,
+                CommandType::CMD_PlaceCharge,
+            ),
+            (
+                CommandType::CMD_CancelProduction,
+                4,
+                1802,
+                CommandType::CMD_DefuseMine,
+            ),
+            (
+                CommandType::PCMD_TentativeUpgradeRemoveAll,
+                0,
+                6,
+                CommandType::PCMD_DetonateCharges,
+            ),
+        ],
+    );
+    let replay = Replay::from_bytes(&patched).unwrap();
+    let commands: Vec<_> = replay
+        .players()
+        .iter()
+        .flat_map(|player| player.commands())
+        .collect();
+
+    let charges: Vec<_> = commands
+        .iter()
+        .filter_map(|command| match command {
+            Command::PlaceCharge(data) => Some(data),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(charges.len(), 2);
+    assert_eq!(charges[0].index(), 5);
+    assert_eq!(charges[0].target(), Some(Target::Entity(1000007969)));
+    assert_eq!(charges[0].selection().squads(), &[0]);
+
+    let defusals = commands
+        .iter()
+        .filter(|command| matches!(command, Command::DefuseCharge(_) | Command::DefuseMine(_)))
+        .count();
+    assert_eq!(defusals, 3);
+
+    let detonation = commands
+        .iter()
+        .find_map(|command| match command {
+            Command::DetonateCharges(data) => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(detonation.index(), 6);
+    assert_eq!(detonation.target(), None);
+    assert!(detonation.selection().is_empty());
+}
+
+#[test]
+fn parse_repairs() {
+    let data = include_bytes!("../replays/USvDAK_v10612.rec");
+
+    // no samples contain these commands, so retype commands issued to the same kinds of
+    // selection. This only checks that each command type is routed to its variant with the
+    // selection and target read where other squad commands keep them, not what a real repair or
+    // teardown carries
+    let patched = retype(
+        data,
+        &[
+            (CommandType::CMD_RallyPoint, 0, 10, CommandType::CMD_Repair),
+            (CommandType::CMD_BuildSquad, 0, 1, CommandType::CMD_Teardown),
+            (CommandType::CMD_Upgrade, 0, 326, CommandType::CMD_Destroy),
+            (CommandType::SCMD_Stop, 0, 79, CommandType::SCMD_Destroy),
+            (
+                CommandType::CMD_BuildSquad,
+                0,
+                8,
+                CommandType::CMD_RestoreWreck,
+            ),
+            (
+                CommandType::CMD_CancelProduction,
+                0,
+                541,
+                CommandType::CMD_CancelRestoreWreck,
+            ),
+        ],
+    );
+    let replay = Replay::from_bytes(&patched).unwrap();
+    let commands = replay.players()[0].commands();
+
+    let repair = commands
+        .iter()
+        .find_map(|command| match command {
+            Command::Repair(data) => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(repair.tick(), 437);
+    assert_eq!(repair.selection().entities(), &[0x214c1]);
+    assert_eq!(repair.target(), Some(Target::Entity(1000141251)));
+
+    let teardown = commands
+        .iter()
+        .find_map(|command| match command {
+            Command::Teardown(data) => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(teardown.tick(), 28);
+    assert_eq!(teardown.selection().entities(), &[0x214c1]);
+    assert_eq!(teardown.target(), None);
+
+    let destroys = commands
+        .iter()
+        .filter_map(|command| match command {
+            Command::Destroy(data) => Some(data),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(destroys.len(), 2);
+    assert_eq!(destroys[0].tick(), 1857);
+    assert_eq!(destroys[0].selection().squads(), &[0x13]);
+    assert_eq!(destroys[1].tick(), 4210);
+    assert_eq!(destroys[1].selection().entities(), &[0x21649]);
+
+    let restore = commands
+        .iter()
+        .find_map(|command| match command {
+            Command::RestoreWreck(data) => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(restore.tick(), 322);
+    assert_eq!(restore.selection().entities(), &[0x214c1]);
+
+    let cancel = commands
+        .iter()
+        .find_map(|command| match command {
+            Command::CancelRestoreWreck(data) => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(cancel.tick(), 5837);
+    assert_eq!(cancel.selection().entities(), &[0x21649]);
+    assert_eq!(cancel.target(), None);
+}
+
+#[test]
+fn parse_formation_groups() {
+    let data = include_bytes!("../replays/one_seven_zero.rec");
+
+    // no samples contain formation commands, so retype player commands for the steps of creating a
+    // group and an SCMD_Load aimed at a squad for the join. Each added squad's argument is copied
+    // from the squad target of a real SCMD_Load, so this exercises the group reconstruction, not
+    // the layout of real formation commands
+    let mut patched = retype(
+        data,
+        &[
+            (
+                CommandType::PCMD_TentativeUpgradeRemoveAll,
+                3,
+                1017,
+                CommandType::PCMD_FormationSquadGroupCreateBegin,
+            ),
+            (
+                CommandType::PCMD_TentativeUpgrade,
+                3,
+                1201,
+                CommandType::PCMD_FormationSquadGroupAddSquad,
+            ),
+            (
+                CommandType::PCMD_TentativeUpgrade,
+                3,
+                1202,
+                CommandType::PCMD_FormationSquadGroupAddSquad,
+            ),
+            (
+                CommandType::PCMD_TentativeUpgradeRemoveAll,
+                3,
+                1204,
+                CommandType::PCMD_FormationSquadGroupCreateEnd,
+            ),
+            (
+                CommandType::SCMD_Load,
+                3,
+                2513,
+                CommandType::SCMD_JoinFormationSquadGroup,
+            ),
+        ],
+    );
+    for (addition, load) in [(1201, 325), (1202, 612)] {
+        let addition = command_offset(data, CommandType::PCMD_TentativeUpgrade, 3, addition);
+        let load = command_offset(data, CommandType::SCMD_Load, 3, load);
+        let target = &data[command_end(data, load) - 7..command_end(data, load)];
+        let end = command_end(data, addition);
+        patched[end - target.len()..end].copy_from_slice(target);
+    }
+
+    let original = Replay::from_bytes(data).unwrap();
+    let replay = Replay::from_bytes(&patched).unwrap();
+    // player ID 3 is ElGranEagle, the last player in the list
+    let (original, player) = (&original.players()[5], &replay.players()[5]);
+    let loads: Vec<_> = original
+        .commands()
+        .into_iter()
+        .filter_map(|command| match command {
+            Command::Load(data) => Some(data),
+            _ => None,
+        })
+        .collect();
+    let squads: Vec<u32> = [325, 612]
+        .iter()
+        .map(|&index| {
+            let load = loads.iter().find(|load| load.index() == index).unwrap();
+            load.target().squad().unwrap()
+        })
+        .collect();
+    let joining = loads
+        .iter()
+        .find(|load| load.index() == 2513)
+        .unwrap()
+        .selection()
+        .squads()
+        .to_vec();
+
+    assert!(player.formation_groups_at(5419).is_empty());
+
+    let created = player.formation_groups_at(5420);
+    assert_eq!(created.len(), 1);
+    assert_eq!(created[0].created_at(), 5420);
+    assert_eq!(created[0].squads(), &squads[..]);
+
+    let joined = player.formation_groups_at(9633);
+    assert_eq!(joined.len(), 1);
+    assert_ne!(joined[0].squads(), created[0].squads());
+    assert_eq!(
+        joined[0].squads(),
+        &[&squads[..], &joining[..]].concat()[..]
+    );
+}
+
+#[test]
+fn parse_cheats() {
+    let data = include_bytes!("../replays/one_seven_zero.rec");
+    assert!(!Replay::from_bytes(data).unwrap().cheats_used());
+
+    // no samples contain cheats, so retype a PCMD_TentativeUpgrade. Cheats are flagged by their
+    // command type alone, so this checks detection rather than the layout of a real cheat
+    let patched = retype(
+        data,
+        &[(
+            CommandType::PCMD_TentativeUpgrade,
+            0,
+            19,
+            CommandType::PCMD_CheatResources,
+        )],
+    );
+    let replay = Replay::from_bytes(&patched).unwrap();
+    assert!(replay.cheats_used());
+
+    let cheats = replay.players()[0].cheats();
+    assert_eq!(cheats.len(), 1);
+    assert_eq!(cheats[0].index(), 19);
+    assert_eq!(cheats[0].kind(), CheatKind::Resources);
+
+    // a cheat that can't be decoded is still flagged: retype a PCMD_TentativeUpgradeRemoveAll and
+    // cut its length short so its index can't be read
+    let mut patched = retype(
+        data,
+        &[(
+            CommandType::PCMD_TentativeUpgradeRemoveAll,
+            0,
+            6,
+            CommandType::PCMD_CheatRevealAll,
+        )],
+    );
+    let offset = command_offset(data, CommandType::PCMD_TentativeUpgradeRemoveAll, 0, 6);
+    patched[offset - 2..offset].copy_from_slice(&6u16.to_le_bytes());
+    let replay = Replay::from_bytes(&patched).unwrap();
+    let player = &replay.players()[0];
+    assert!(player.cheats().is_empty());
+    assert!(player.commands().iter().any(|command| matches!(
+        command,
+        Command::Malformed(data) if data.action_type() == CommandType::PCMD_CheatRevealAll
+    )));
+    assert!(player.cheated());
+    assert!(replay.cheats_used());
+}
+
+#[test]
+fn parse_batch() {
+    let options = BatchOptions {
+        workers: 2,
+        ..BatchOptions::default()
+    };
+    let mut batch = batch::parse_dir("replays", &options).unwrap();
+    let first = batch.next().unwrap();
+    assert!(first.1.is_ok());
+
+    let report = batch.report();
+    assert_eq!(report.successes(), 7);
+    assert_eq!(report.failure_count(), 0);
+
+    let err = batch::parse_dir("replays/missing", &options).unwrap_err();
+    assert_eq!(err.path(), Some(Path::new("replays/missing")));
+}
+
+#[test]
+fn parse_batch_panic() {
+    // an unknown faction makes the parser panic, which should be reported as a failure rather
+    // than aborting the batch
+    let dir = std::env::temp_dir().join(format!("vault_batch_panic_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut data = include_bytes!("../replays/vs_ai.rec").to_vec();
+    let faction = data
+        .windows(b"germans".len())
+        .position(|window| window == b"germans")
+        .unwrap();
+    data[faction] = b'x';
+    std::fs::write(dir.join("bad_faction.rec"), data).unwrap();
+    std::fs::copy("replays/skirmish.rec", dir.join("skirmish.rec")).unwrap();
+
+    let report = batch::parse_dir(&dir, &BatchOptions::default())
+        .unwrap()
+        .report();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(report.successes(), 1);
+    assert_eq!(report.failure_count(), 1);
+    let group = report.failures().keys().next().unwrap();
+    assert_eq!(group.kind, FailureKind::Panic);
+}
+
 #[test]
 fn parse_weird_description() {
     let data = include_bytes!("../replays/weird_description.rec");
@@ -124,106 +1026,274 @@ fn parse_new_map_chunk() {
 }
 
 #[test]
-#[cfg_attr(not(feature = "regression"), ignore)]
-fn regression() {
-    let paths = fs::read_dir("replays/regression").unwrap();
-    let pathbufs: Vec<_> = paths
+fn parse_items() {
+    let data = include_bytes!("../replays/USvDAK_v10612.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    let player = &replay.players()[0];
+    let items = player.items();
+
+    assert_eq!(items.len(), 28);
+    assert_eq!(items[0].iteminstance_id(), 1800799);
+    assert_eq!(items[0].itemdefinition_id(), 453294);
+    assert_eq!(items[0].durability(), 1);
+    assert_eq!(items[0].metadata(), "{\"epos\":\"0\",\"eslot\":\"0\"}");
+    assert_eq!(items[0].itemlocation_id(), 1800791);
+    assert!(items
+        .iter()
+        .all(|item| Some(item.profile_id()) == player.profile_id()));
+
+    let data = include_bytes!("../replays/vs_ai.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    assert!(replay.players()[1].items().is_empty());
+}
+
+#[test]
+fn parse_message_channels() {
+    let data = include_bytes!("../replays/one_seven_zero.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    let player = replay
+        .players()
         .into_iter()
-        .map(|path| path.unwrap().path())
-        .filter(|path| path.is_file())
-        .collect();
-    let chunks = pathbufs.chunks(100);
-    let mut results = Vec::new();
+        .find(|player| player.name() == "ElGranEagle")
+        .unwrap();
+    let messages = player.messages();
+
+    assert_eq!(messages[0].channel(), MessageChannel::Team);
+    assert_eq!(messages[3].message(), "Lol gj all");
+    assert_eq!(messages[3].channel(), MessageChannel::All);
+    assert!(messages
+        .iter()
+        .all(|message| message.sender_id() == player.profile_id()));
+
+    let ally = replay
+        .players()
+        .into_iter()
+        .find(|player| player.name() == "WhyUSayOMG")
+        .unwrap();
+    let messages = ally.messages();
+    let report = messages
+        .iter()
+        .find(|message| message.message() == "please report SARGENTOSAM for griefing")
+        .unwrap();
+
+    assert_eq!(messages[0].channel(), MessageChannel::Team);
+    assert_eq!(report.channel(), MessageChannel::All);
+
+    let data = include_bytes!("../replays/USvDAK_v10612.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    let players = replay.players();
+
+    assert_eq!(players[0].messages()[0].message(), "glhf");
+    assert!(players
+        .iter()
+        .flat_map(|player| player.messages())
+        .all(|message| message.channel() == MessageChannel::All));
+}
+
+#[test]
+fn parse_messages_duplicate_names() {
+    // give both players the same name by pseudonymizing only their names, then renaming the
+    // second pseudonym to the first, which has the same length
+    let data = include_bytes!("../replays/USvDAK_v10612.rec");
+    let options = AnonymizeOptions {
+        steam_ids: false,
+        profile_ids: false,
+        ..AnonymizeOptions::default()
+    };
+    let mut renamed = anonymize(data, &options).unwrap();
+    let (first, second) = (utf16("Player 1"), utf16("Player 2"));
+    for idx in 0..renamed.len() - second.len() {
+        if renamed[idx..idx + second.len()] == second[..] {
+            renamed[idx..idx + second.len()].copy_from_slice(&first);
+        }
+    }
+
+    let replay = Replay::from_bytes(&renamed).unwrap();
+    let players = replay.players();
+
+    assert_eq!(players[0].name(), "Player 1");
+    assert_eq!(players[1].name(), "Player 1");
+    assert_eq!(
+        players[0]
+            .messages()
+            .iter()
+            .map(|message| message.message())
+            .collect::<Vec<&str>>(),
+        vec![
+            "glhf",
+            "gg",
+            "dak reinforcements are too expensive",
+            "i have 250 fuel",
+            "no way to spend it"
+        ]
+    );
+    assert_eq!(
+        players[1]
+            .messages()
+            .iter()
+            .map(|message| message.message())
+            .collect::<Vec<&str>>(),
+        vec![
+            "no u",
+            "gg",
+            "DAK is a hard faction and needs buffs for sure"
+        ]
+    );
+}
 
-    for chunk in chunks {
-        let handles: Vec<_> = chunk
+#[test]
+fn anonymize_replay() {
+    let data = include_bytes!("../replays/USvDAK_v10612.rec");
+    let original = Replay::from_bytes(data).unwrap();
+    let anonymized = anonymize(data, &AnonymizeOptions::default()).unwrap();
+    let replay = Replay::from_bytes(&anonymized).unwrap();
+
+    assert_eq!(
+        replay
+            .players()
             .iter()
-            .map(|path| {
-                let cloned_path = path.clone();
-                thread::spawn(move || {
-                    let mut file = File::open(cloned_path.clone()).unwrap();
-                    let mut buffer = Vec::new();
-                    file.read_to_end(&mut buffer).unwrap();
-
-                    match Replay::from_bytes(&buffer) {
-                        Ok(replay) => Ok(replay),
-                        Err(_) => Err(format!("failed to parse {:?}", cloned_path)),
-                    }
-                })
-            })
-            .collect();
-
-        let mut parse_results: Vec<_> = handles
-            .into_iter()
-            .map(|handle| handle.join().unwrap())
-            .collect();
-        results.append(&mut parse_results);
+            .map(|player| { player.name() })
+            .collect::<Vec<&str>>(),
+        vec!["Player 1", "Player 2"]
+    );
+    assert_eq!(
+        replay
+            .players()
+            .iter()
+            .map(|player| { player.steam_id() })
+            .collect::<Vec<Option<u64>>>(),
+        vec![Some(76561197960265729), Some(76561197960265730)]
+    );
+    assert_eq!(
+        replay
+            .players()
+            .iter()
+            .map(|player| { player.profile_id() })
+            .collect::<Vec<Option<u64>>>(),
+        vec![Some(1), Some(2)]
+    );
+    assert_eq!(replay.length(), original.length());
+    assert_eq!(replay.players()[0].messages()[0].message(), "glhf");
+    assert_eq!(
+        replay.players()[0].commands().len(),
+        original.players()[0].commands().len()
+    );
+
+    for personal in ["madhax", "Quixalotl", "76561198403218853"] {
+        let needle = utf16(personal);
+        assert!(!anonymized
+            .windows(needle.len())
+            .any(|window| window == needle.as_slice()));
     }
+}
 
-    let errs: Vec<_> = results.iter().filter(|result| result.is_err()).collect();
-    assert_eq!(errs.len(), 0);
+#[test]
+fn anonymize_replay_redacted() {
+    let data = include_bytes!("../replays/one_seven_zero.rec");
+    let options = AnonymizeOptions {
+        redact_messages: true,
+        ..AnonymizeOptions::default()
+    };
+    let replay = Replay::from_bytes(&anonymize(data, &options).unwrap()).unwrap();
+    let messages: Vec<_> = replay
+        .players()
+        .iter()
+        .flat_map(|player| player.messages())
+        .collect();
+
+    assert_eq!(messages.len(), 20);
+    assert!(messages
+        .iter()
+        .all(|message| message.message() == "[redacted]"));
+}
+
+#[test]
+#[cfg_attr(not(feature = "regression"), ignore)]
+fn regression() {
+    let report = batch::parse_dir("replays/regression", &regression_options())
+        .unwrap()
+        .report();
+
+    println!("{:?}", report.failures());
+    assert_eq!(report.failure_count(), 0);
 }
 
 #[test]
 #[cfg_attr(not(feature = "missing"), ignore)]
 fn missing_commands() {
-    let paths = fs::read_dir("replays/regression").unwrap();
-    let pathbufs: Vec<_> = paths
-        .into_iter()
-        .map(|path| path.unwrap().path())
-        .filter(|path| path.is_file())
+    let types: HashSet<CommandType> = batch::parse_dir("replays/regression", &regression_options())
+        .unwrap()
+        .filter_map(|(_, result)| result.ok())
+        .flat_map(|replay| replay.players())
+        .flat_map(|player| player.commands())
+        .filter_map(|command| {
+            if let Command::Unknown(data) = command {
+                if data.index() != 0 {
+                    Some(data.action_type())
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        })
         .collect();
-    let chunks = pathbufs.chunks(100);
-    let mut results: Vec<Result<HashSet<CommandType>, String>> = Vec::new();
 
-    for chunk in chunks {
-        let handles: Vec<_> = chunk
-            .iter()
-            .map(|path| {
-                let cloned_path = path.clone();
-                thread::spawn(move || {
-                    let mut file = File::open(cloned_path.clone()).unwrap();
-                    let mut buffer = Vec::new();
-                    file.read_to_end(&mut buffer).unwrap();
-
-                    match Replay::from_bytes(&buffer) {
-                        Ok(replay) => Ok(HashSet::from_iter(
-                            replay
-                                .players()
-                                .iter()
-                                .flat_map(|player| player.commands())
-                                .filter_map(|command| {
-                                    if let Command::Unknown(data) = command {
-                                        if data.index() != 0 {
-                                            Some(data.action_type())
-                                        } else {
-                                            None
-                                        }
-                                    } else {
-                                        None
-                                    }
-                                }),
-                        )),
-                        Err(_) => Err(format!("failed to parse {:?}", cloned_path)),
-                    }
-                })
-            })
-            .collect();
-
-        let mut parse_results: Vec<_> = handles
-            .into_iter()
-            .map(|handle| handle.join().unwrap())
-            .collect();
-        results.append(&mut parse_results);
+    println!("{:?}", types);
+    assert_eq!(types.len(), 0);
+}
+
+fn regression_options() -> BatchOptions {
+    BatchOptions {
+        recursive: false,
+        extension: None,
+        ..BatchOptions::default()
     }
+}
 
-    let types = results
-        .iter()
-        .map(|result| result.clone().unwrap_or_default())
-        .reduce(|mut acc, set| {
-            acc.extend(set);
-            acc
-        });
-    println!("{:?}", types.clone().unwrap());
-    assert_eq!(types.unwrap().len(), 0);
+fn utf16(value: &str) -> Vec<u8> {
+    value
+        .encode_utf16()
+        .flat_map(|unit| unit.to_le_bytes())
+        .collect()
+}
+
+/// Rewrites the type of each given command, identified by its current type, the ID of the player
+/// that issued it and its index. Only used for command types that no sample replay contains: the
+/// rest of each command keeps the layout of the command it replaced, so tests using this can only
+/// check routing and the parts of the layout the two types share, not real field values.
+fn retype(data: &[u8], commands: &[(CommandType, u8, u32, CommandType)]) -> Vec<u8> {
+    let mut patched = data.to_vec();
+
+    for &(action_type, player_id, index, retyped) in commands {
+        patched[command_offset(data, action_type, player_id, index)] = retyped.into();
+    }
+
+    patched
+}
+
+/// The offset just past the end of the command whose type byte is at `offset`.
+fn command_end(data: &[u8], offset: usize) -> usize {
+    offset - 2 + u16::from_le_bytes([data[offset - 2], data[offset - 1]]) as usize
+}
+
+/// Finds the offset of the type byte of the given command, panicking unless exactly one command
+/// in the replay matches. Commands are at most a few hundred bytes long, which rules out most
+/// matches within unrelated data.
+fn command_offset(data: &[u8], action_type: CommandType, player_id: u8, index: u32) -> usize {
+    let action_type: u8 = action_type.into();
+    let offsets: Vec<usize> = (2..data.len() - 6)
+        .filter(|&offset| {
+            let length = u16::from_le_bytes([data[offset - 2], data[offset - 1]]) as usize;
+
+            data[offset] == action_type
+                && data[offset + 1] & 0b0111_1111 == player_id
+                && data[offset + 2..offset + 6] == index.to_le_bytes()
+                && (7..0x400).contains(&length)
+                && offset - 2 + length <= data.len()
+        })
+        .collect();
+
+    assert_eq!(offsets.len(), 1, "{} commands match", offsets.len());
+    offsets[0]
 }